@@ -1,15 +1,24 @@
 #![allow(dead_code, unused_macros)]
 use std::{
+    any::TypeId,
+    cell::Cell,
+    collections::{BTreeMap, HashMap},
     fmt::{Display, Debug},
-    cmp::Ordering, ops::{Add, Sub, Mul, Div, Neg}
+    cmp::Ordering, hash::{DefaultHasher, Hash, Hasher}, iter::{Sum, Product}, ops::{Add, Sub, Mul, Div, Rem, Neg, AddAssign, SubAssign, MulAssign, DivAssign},
+    time::Duration
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NativeUnit {
     Meter,
     Liter,
     Gramm,
-    Second, Minute, Hour, Day, Week, Year
+    Second, Minute, Hour, Day, Week, Year,
+    Ampere,
+    Kelvin,
+    Mole,
+    Candela
 }
 impl Display for NativeUnit {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -23,52 +32,579 @@ impl Display for NativeUnit {
             Self::Day => write!(f, "d"),
             Self::Week => write!(f, "w"),
             Self::Year => write!(f, "y"),
+            Self::Ampere => write!(f, "A"),
+            Self::Kelvin => write!(f, "K"),
+            Self::Mole => write!(f, "mol"),
+            Self::Candela => write!(f, "cd"),
+        }
+    }
+}
+impl NativeUnit {
+    pub const ALL: [Self; 13] = [
+        Self::Meter,
+        Self::Liter,
+        Self::Gramm,
+        Self::Second, Self::Minute, Self::Hour, Self::Day, Self::Week, Self::Year,
+        Self::Ampere,
+        Self::Kelvin,
+        Self::Mole,
+        Self::Candela
+    ];
+    pub fn dimension(&self) -> BaseDimension {
+        match self {
+            Self::Meter => BaseDimension::Length,
+            Self::Liter => BaseDimension::Volume,
+            Self::Gramm => BaseDimension::Mass,
+            Self::Second | Self::Minute | Self::Hour | Self::Day | Self::Week | Self::Year => BaseDimension::Time,
+            Self::Ampere => BaseDimension::Current,
+            Self::Kelvin => BaseDimension::Temperature,
+            Self::Mole => BaseDimension::Amount,
+            Self::Candela => BaseDimension::LuminousIntensity,
+        }
+    }
+    // how many of its dimension's base unit one of this unit is
+    pub fn factor(&self) -> f64 {
+        match self {
+            Self::Meter | Self::Liter | Self::Gramm | Self::Second
+                | Self::Ampere | Self::Kelvin | Self::Mole | Self::Candela => 1.,
+            Self::Minute => 60.,
+            Self::Hour => 3600.,
+            Self::Day => 86400.,
+            Self::Week => 604800.,
+            Self::Year => 31536000.,
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SiPrefix {
+    Giga, Mega, Kilo,
+    Centi, Milli, Micro, Nano
+}
+impl Display for SiPrefix {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Giga => write!(f, "G"),
+            Self::Mega => write!(f, "M"),
+            Self::Kilo => write!(f, "k"),
+            Self::Centi => write!(f, "c"),
+            Self::Milli => write!(f, "m"),
+            Self::Micro => write!(f, "µ"),
+            Self::Nano => write!(f, "n"),
+        }
+    }
+}
+impl SiPrefix {
+    pub const ALL: [Self; 7] = [
+        Self::Giga, Self::Mega, Self::Kilo,
+        Self::Centi, Self::Milli, Self::Micro, Self::Nano
+    ];
+    pub fn factor(&self) -> f64 {
+        match self {
+            Self::Giga => 1e9,
+            Self::Mega => 1e6,
+            Self::Kilo => 1e3,
+            Self::Centi => 1e-2,
+            Self::Milli => 1e-3,
+            Self::Micro => 1e-6,
+            Self::Nano => 1e-9,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BaseDimension {
+    Length,
+    Mass,
+    Time,
+    Volume,
+    Current,
+    Temperature,
+    Amount,
+    LuminousIntensity,
+}
+impl BaseDimension {
+    // dimension symbols as used in dimensional analysis
+    fn symbol(&self) -> &'static str {
+        match self {
+            Self::Length => "L",
+            Self::Mass => "M",
+            Self::Time => "T",
+            Self::Volume => "L^3",
+            Self::Current => "I",
+            Self::Temperature => "Θ",
+            Self::Amount => "N",
+            Self::LuminousIntensity => "J",
+        }
+    }
+    pub fn base_unit(&self) -> NativeUnit {
+        match self {
+            Self::Length => NativeUnit::Meter,
+            Self::Mass => NativeUnit::Gramm,
+            Self::Time => NativeUnit::Second,
+            Self::Volume => NativeUnit::Liter,
+            Self::Current => NativeUnit::Ampere,
+            Self::Temperature => NativeUnit::Kelvin,
+            Self::Amount => NativeUnit::Mole,
+            Self::LuminousIntensity => NativeUnit::Candela,
+        }
+    }
+}
+
+// kelvin is the native unit, celsius and fahrenheit are the custom units °C and °F
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TempScale {
+    Kelvin,
+    Celsius,
+    Fahrenheit
+}
+impl TempScale {
+    pub fn unit(&self) -> UnitKind {
+        match self {
+            Self::Kelvin => UnitKind::Native(NativeUnit::Kelvin),
+            Self::Celsius => UnitKind::Custom("°C".to_string()),
+            Self::Fahrenheit => UnitKind::Custom("°F".to_string()),
+        }
+    }
+    fn of(unit: &UnitKind) -> Option<Self> {
+        [Self::Kelvin, Self::Celsius, Self::Fahrenheit].into_iter().find(|scale| scale.unit() == *unit)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnitPreference {
+    Larger,
+    Smaller,
+    Left,
+    Right
+}
+
+// ordered like BaseDimension so they can be compared against base_exponents
+const NAMED_DIMENSIONS: [(&str, &[(BaseDimension, i32)]); 9] = [
+    ("area", &[(BaseDimension::Length, 2)]),
+    ("volume", &[(BaseDimension::Length, 3)]),
+    ("speed", &[(BaseDimension::Length, 1), (BaseDimension::Time, -1)]),
+    ("acceleration", &[(BaseDimension::Length, 1), (BaseDimension::Time, -2)]),
+    ("hertz", &[(BaseDimension::Time, -1)]),
+    ("newton", &[(BaseDimension::Length, 1), (BaseDimension::Mass, 1), (BaseDimension::Time, -2)]),
+    ("pascal", &[(BaseDimension::Length, -1), (BaseDimension::Mass, 1), (BaseDimension::Time, -2)]),
+    ("joule", &[(BaseDimension::Length, 2), (BaseDimension::Mass, 1), (BaseDimension::Time, -2)]),
+    ("watt", &[(BaseDimension::Length, 2), (BaseDimension::Mass, 1), (BaseDimension::Time, -3)]),
+];
+const US_GALLON: f64 = 3.785411784;
+const MILE: f64 = 1609.344;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnitSystem {
+    SI,
+    Imperial,
+    USCustomary
+}
+impl UnitSystem {
+    // the units the system uses for a dimension and how many base units one of each is,
+    // smallest first, only length, mass and volume differ from SI
+    fn units_for(&self, dimension: BaseDimension) -> Vec<(UnitKind, f64)> {
+        match (self, dimension) {
+            (Self::SI, dimension)
+            | (_, dimension @ (BaseDimension::Time | BaseDimension::Current | BaseDimension::Temperature
+                | BaseDimension::Amount | BaseDimension::LuminousIntensity)) => NativeUnit::ALL.into_iter()
+                .filter(|unit| unit.dimension() == dimension)
+                .map(|unit| (UnitKind::Native(unit), unit.factor()))
+                .chain((dimension == BaseDimension::Length).then_some((UnitKind::Prefixed(SiPrefix::Kilo, NativeUnit::Meter), 1000.)))
+                .collect(),
+            (_, BaseDimension::Length) => vec![
+                (UnitKind::Custom("ft".to_string()), 0.3048),
+                (UnitKind::Custom("mi".to_string()), MILE),
+            ],
+            (_, BaseDimension::Mass) => vec![(UnitKind::Custom("lb".to_string()), 453.59237)],
+            (Self::Imperial, BaseDimension::Volume) => vec![(UnitKind::Custom("imp_gal".to_string()), 4.54609)],
+            (Self::USCustomary, BaseDimension::Volume) => vec![(UnitKind::Custom("gal".to_string()), US_GALLON)],
+        }
+    }
+    fn unit_for(&self, dimension: BaseDimension) -> (UnitKind, f64) {
+        self.units_for(dimension).swap_remove(0)
+    }
+}
+
+#[derive(Debug, Clone, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnitKind {
     Pro(Box<Self>, Box<Self>), // km / h
     Per(Box<Self>, Box<Self>), // m * s
     Pow(Box<Self>, usize), // m ^ 2
     Native(NativeUnit),
+    Prefixed(SiPrefix, NativeUnit), // km
     Custom(String),
     None
 }
+// multiplication commutes, so m*s and s*m are the same unit
+impl PartialEq for UnitKind {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Native(native1), Self::Native(native2)) => native1 == native2,
+            (Self::Prefixed(prefix1, native1), Self::Prefixed(prefix2, native2)) => prefix1 == prefix2 && native1 == native2,
+            (Self::Custom(unit1), Self::Custom(unit2)) => unit1 == unit2,
+            (Self::Pro(u1, u2), Self::Pro(u3, u4)) => u1 == u3 && u2 == u4,
+            (Self::Per(u1, u2), Self::Per(u3, u4)) => (u1 == u3 && u2 == u4) || (u1 == u4 && u2 == u3),
+            (Self::Pow(unit1, pow1), Self::Pow(unit2, pow2)) => unit1 == unit2 && pow1 == pow2,
+            (Self::None, Self::None) => true,
+            _ => false,
+        }
+    }
+}
+impl Hash for UnitKind {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Self::Native(native) => native.hash(state),
+            Self::Prefixed(prefix, native) => {
+                prefix.hash(state);
+                native.hash(state);
+            }
+            Self::Custom(unit) => unit.hash(state),
+            Self::Pro(u1, u2) => {
+                u1.hash(state);
+                u2.hash(state);
+            }
+            // the operands are combined order independently to agree with eq
+            Self::Per(u1, u2) => {
+                let hash = |unit: &Self| {
+                    let mut hasher = DefaultHasher::new();
+                    unit.hash(&mut hasher);
+                    hasher.finish()
+                };
+                hash(u1).wrapping_add(hash(u2)).hash(state)
+            }
+            Self::Pow(unit, pow) => {
+                unit.hash(state);
+                pow.hash(state);
+            }
+            Self::None => {}
+        }
+    }
+}
+// {:#} renders powers as superscripts and products with a middle dot
 impl Display for UnitKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write(f, f.alternate())
+    }
+}
+fn superscript(digits: &str) -> String {
+    digits.chars().map(|c| match c {
+        '-' => '⁻',
+        c => "⁰¹²³⁴⁵⁶⁷⁸⁹".chars().nth(c as usize - '0' as usize).unwrap(),
+    }).collect()
+}
+impl UnitKind {
+    fn is_compound(&self) -> bool {
+        matches!(self, Self::Pro(..) | Self::Per(..))
+    }
+    // compound right operands and bases get parentheses so the output parses back the same,
+    // unicode swaps in superscript powers and a middle dot
+    fn write(&self, f: &mut std::fmt::Formatter<'_>, unicode: bool) -> std::fmt::Result {
         match self {
             Self::Native(native) => write!(f, "{native}"),
+            Self::Prefixed(prefix, native) => write!(f, "{prefix}{native}"),
             Self::Custom(unit) => write!(f, "{unit}"),
-            Self::Pro(u1, u2) => write!(f, "{u1}/{u2}"),
-            Self::Per(u1, u2) => write!(f, "{u1}*{u2}"),
-            Self::Pow(unit, pow) => write!(f, "{unit}^{pow}"),
+            // a bare denominator reads as a negative power, /s as s⁻¹
+            Self::Pro(u1, u2) if unicode && **u1 == Self::None => match &**u2 {
+                Self::Pow(unit, pow) => {
+                    unit.write_operand(f, unicode)?;
+                    write!(f, "{}", superscript(&format!("-{pow}")))
+                }
+                unit => {
+                    unit.write_operand(f, unicode)?;
+                    write!(f, "⁻¹")
+                }
+            },
+            Self::Pro(u1, u2) => {
+                u1.write(f, unicode)?;
+                write!(f, "/")?;
+                u2.write_operand(f, unicode)
+            }
+            Self::Per(u1, u2) => {
+                u1.write(f, unicode)?;
+                write!(f, "{}", if unicode { "·" } else { "*" })?;
+                u2.write_operand(f, unicode)
+            }
+            Self::Pow(unit, pow) => {
+                unit.write_operand(f, unicode)?;
+                if unicode {
+                    write!(f, "{}", superscript(&pow.to_string()))
+                } else {
+                    write!(f, "^{pow}")
+                }
+            }
             Self::None => Ok(()),
         }
     }
+    fn write_operand(&self, f: &mut std::fmt::Formatter<'_>, unicode: bool) -> std::fmt::Result {
+        if self.is_compound() {
+            write!(f, "(")?;
+            self.write(f, unicode)?;
+            write!(f, ")")
+        } else {
+            self.write(f, unicode)
+        }
+    }
+    // unit raised to pow, without degenerate powers of 0 and 1
+    fn pow(unit: Self, pow: usize) -> Self {
+        match pow {
+            0 => Self::None,
+            1 => unit,
+            pow => Self::Pow(Box::new(unit), pow),
+        }
+    }
+    // unit raised to an integer power, negative powers go into a denominator
+    fn powi(unit: Self, pow: i64) -> Self {
+        match pow {
+            pow if pow < 0 => Self::Pro(Box::new(Self::None), Box::new(Self::pow(unit, pow.unsigned_abs() as usize))),
+            pow => Self::pow(unit, pow as usize),
+        }
+    }
+    // every exponent times num/den, None if one of them doesn't come out whole
+    fn pow_rational(self, num: i64, den: i64) -> Option<Self> {
+        let whole = |pow: i64| (pow % den == 0).then_some(pow / den);
+        Some(match self {
+            Self::None => Self::None,
+            Self::Pow(unit, pow) => Self::powi(*unit, whole(pow as i64 * num)?),
+            Self::Per(u1, u2) => match (u1.pow_rational(num, den)?, u2.pow_rational(num, den)?) {
+                (Self::None, unit) | (unit, Self::None) => unit,
+                (u1, u2) => Self::Per(Box::new(u1), Box::new(u2)),
+            },
+            Self::Pro(u1, u2) => match (u1.pow_rational(num, den)?, u2.pow_rational(num, den)?) {
+                (unit, Self::None) => unit,
+                (u1, u2) => Self::Pro(Box::new(u1), Box::new(u2)),
+            },
+            unit => Self::powi(unit, whole(num)?),
+        })
+    }
+    // the native unit and its factor to the dimension's base unit, prefixes included
+    fn native_factor(&self) -> Option<(NativeUnit, f64)> {
+        match self {
+            Self::Native(unit) => Some((*unit, unit.factor())),
+            Self::Prefixed(prefix, unit) => Some((*unit, prefix.factor() * unit.factor())),
+            _ => None,
+        }
+    }
+    // calls leaf with every native, prefixed and custom unit and its net exponent,
+    // None once a nested power doesn't fit into an i32 or leaf gives up
+    fn walk(&self, pow: i32, leaf: &mut impl FnMut(&Self, i32) -> Option<()>) -> Option<()> {
+        match self {
+            Self::Pow(unit, n) => unit.walk(pow.checked_mul(i32::try_from(*n).ok()?)?, leaf),
+            Self::Per(unit1, unit2) => {
+                unit1.walk(pow, leaf)?;
+                unit2.walk(pow, leaf)
+            }
+            Self::Pro(unit1, unit2) => {
+                unit1.walk(pow, leaf)?;
+                unit2.walk(pow.checked_neg()?, leaf)
+            }
+            Self::None => Some(()),
+            unit => leaf(unit, pow),
+        }
+    }
+    pub(crate) fn exponents_fit(&self) -> bool {
+        self.walk(1, &mut |_, _| Some(())).is_some()
+    }
+    // net exponents keyed by base unit, so h counts as s and l as m^3,
+    // custom units are left out and compared by name in same_dimension,
+    // None when an exponent doesn't fit into an i32
+    pub fn dimension(&self) -> Option<BTreeMap<NativeUnit, i32>> {
+        let mut exponents = BTreeMap::new();
+        self.walk(1, &mut |unit, pow| {
+            let Some((unit, _)) = unit.native_factor() else {
+                return Some(())
+            };
+            let (unit, pow) = match unit.dimension() {
+                BaseDimension::Volume => (NativeUnit::Meter, pow.checked_mul(3)?),
+                dimension => (dimension.base_unit(), pow),
+            };
+            let exponent: &mut i32 = exponents.entry(unit).or_default();
+            *exponent = exponent.checked_add(pow)?;
+            Some(())
+        })?;
+        exponents.retain(|_, pow| *pow != 0);
+        Some(exponents)
+    }
+    // cancels opposing exponents of the same unit and rebuilds positive powers over negative ones,
+    // natives come first in declaration order, then prefixed and custom units,
+    // units are never swapped for their base unit since there's no value to rescale,
+    // a unit whose exponents don't fit into an i32 is left as it is
+    pub fn simplify(self) -> Self {
+        let mut exponents: Vec<(Self, i32)> = vec![];
+        let fits = self.walk(1, &mut |unit, pow| {
+            match exponents.iter_mut().find(|(other, _)| other == unit) {
+                Some((_, exponent)) => *exponent = exponent.checked_add(pow)?,
+                None => exponents.push((unit.clone(), pow)),
+            }
+            Some(())
+        });
+        if fits.is_none() {
+            return self
+        }
+        exponents.retain(|(_, pow)| *pow != 0);
+        exponents.sort_by_key(|(unit, _)| match unit {
+            Self::Native(unit) => (0, *unit as usize, String::new()),
+            Self::Prefixed(prefix, unit) => (1, *unit as usize, prefix.to_string()),
+            unit => (2, 0, unit.to_string()),
+        });
+        let product = |exponents: Vec<(Self, i32)>| exponents.into_iter()
+            .map(|(unit, pow)| Self::pow(unit, pow.unsigned_abs() as usize))
+            .reduce(|unit1, unit2| Self::Per(Box::new(unit1), Box::new(unit2)));
+        let (positive, negative) = exponents.into_iter().partition(|(_, pow)| *pow > 0);
+        match (product(positive), product(negative)) {
+            (Some(unit1), Some(unit2)) => Self::Pro(Box::new(unit1), Box::new(unit2)),
+            (Some(unit), None) => unit,
+            (None, Some(unit)) => Self::Pro(Box::new(Self::None), Box::new(unit)),
+            (None, None) => Self::None,
+        }
+    }
+    fn custom_exponents(&self) -> Option<BTreeMap<String, i32>> {
+        let mut exponents = BTreeMap::new();
+        self.walk(1, &mut |unit, pow| {
+            if let Self::Custom(name) = unit {
+                let exponent: &mut i32 = exponents.entry(name.clone()).or_default();
+                *exponent = exponent.checked_add(pow)?;
+            }
+            Some(())
+        })?;
+        exponents.retain(|_, pow| *pow != 0);
+        Some(exponents)
+    }
+    // units whose exponents don't fit into an i32 never share a dimension
+    pub fn same_dimension(&self, other: &Self) -> bool {
+        let exponents = |unit: &Self| Some((unit.dimension()?, unit.custom_exponents()?));
+        exponents(self).is_some_and(|exponents1| exponents(other) == Some(exponents1))
+    }
+    // exponents of the base dimensions, None with any custom unit or an exponent that overflows
+    fn base_exponents(&self) -> Option<BTreeMap<BaseDimension, i32>> {
+        let mut custom = false;
+        self.walk(1, &mut |unit, _| {
+            custom |= matches!(unit, Self::Custom(_));
+            Some(())
+        })?;
+        if custom {
+            return None
+        }
+        Some(self.dimension()?.into_iter().map(|(unit, pow)| (unit.dimension(), pow)).collect())
+    }
+    // the highest power any unit is raised to, with nested powers multiplied out
+    fn max_exponent(&self) -> usize {
+        match self {
+            Self::Pow(unit, pow) => pow.saturating_mul(unit.max_exponent().max(1)),
+            Self::Pro(unit1, unit2) | Self::Per(unit1, unit2) => unit1.max_exponent().max(unit2.max_exponent()),
+            Self::None => 0,
+            _ => 1,
+        }
+    }
+    // None stays None, a division flips and everything else goes under None/
+    pub fn recip(self) -> Self {
+        match self {
+            Self::None => Self::None,
+            Self::Pro(unit1, unit2) if *unit1 == Self::None => *unit2,
+            Self::Pro(unit1, unit2) => Self::Pro(unit2, unit1),
+            unit => Self::Pro(Box::new(Self::None), Box::new(unit)),
+        }
+    }
+    // drops powers of 0 and 1 and flattens powers of powers
+    pub fn canonicalize(self) -> Self {
+        match self {
+            Self::Pow(unit, pow) => match unit.canonicalize() {
+                Self::Pow(unit, inner) => Self::pow(*unit, inner * pow),
+                Self::None => Self::None,
+                unit => Self::pow(unit, pow),
+            },
+            Self::Pro(u1, u2) => Self::Pro(Box::new(u1.canonicalize()), Box::new(u2.canonicalize())),
+            Self::Per(u1, u2) => Self::Per(Box::new(u1.canonicalize()), Box::new(u2.canonicalize())),
+            unit => unit,
+        }
+    }
+    // the unit of a product, shared by Mul and MulAssign,
+    // a dimensionless operand leaves the other unit as it is, so 2 * 3m is 6m and not None*m
+    fn times(self, rhs: Self) -> Self {
+        match (self, rhs) {
+            (Self::None, unit) | (unit, Self::None) => unit,
+            (Self::Pow(unit1, pow1), Self::Pow(unit2, pow2)) if unit1 == unit2 => Self::Pow(unit1, pow1 + pow2),
+            (Self::Pow(unit1, pow), unit2) | (unit2, Self::Pow(unit1, pow)) if *unit1 == unit2 => Self::Pow(unit1, pow + 1),
+            (Self::Pro(unit1, unit2), unit3) if *unit2 == unit3 => *unit1,
+            (unit1, unit2) if unit1 == unit2 => Self::Pow(Box::new(unit1), 2),
+            (unit1, unit2) => Self::Per(Box::new(unit1), Box::new(unit2)),
+        }
+    }
+    // the unit of a quotient, a larger power in the divisor leaves None/base^(m-n),
+    // dividing by a dimensionless quantity keeps the unit, while None/m stays the reciprocal
+    fn over(self, rhs: Self) -> Self {
+        match (self, rhs) {
+            (unit, Self::None) => unit,
+            (Self::Pow(unit1, pow1), Self::Pow(unit2, pow2)) if unit1 == unit2 =>
+                Self::powi(*unit1, pow1 as i64 - pow2 as i64),
+            (Self::Pow(unit1, pow), unit2) if *unit1 == unit2 => Self::powi(*unit1, pow as i64 - 1),
+            (unit1, Self::Pow(unit2, pow)) if unit1 == *unit2 => Self::powi(unit1, 1 - pow as i64),
+            (Self::Per(unit1, unit2), unit3) if *unit2 == unit3 => *unit1,
+            (unit1, unit2) if unit1 == unit2 => Self::None,
+            (unit1, unit2) => Self::Pro(Box::new(unit1), Box::new(unit2)),
+        }
+    }
+    // cancels a division nested in a multiplication and the other way around
+    pub fn reduce(self) -> Self {
+        match self {
+            Self::Per(unit1, unit2) => match (unit1.reduce(), unit2.reduce()) {
+                (Self::Pro(unit1, unit2), unit3) | (unit3, Self::Pro(unit1, unit2)) if *unit2 == unit3 => *unit1,
+                (unit1, unit2) => Self::Per(Box::new(unit1), Box::new(unit2)),
+            },
+            Self::Pro(unit1, unit2) => match (unit1.reduce(), unit2.reduce()) {
+                (Self::Per(unit1, unit2), unit3) | (Self::Per(unit2, unit1), unit3) if *unit2 == unit3 => *unit1,
+                (unit1, unit2) if unit1 == unit2 => Self::None,
+                (Self::Pow(unit1, pow), unit2) if *unit1 == unit2 => Self::powi(*unit1, pow as i64 - 1),
+                (Self::Pow(unit1, pow1), Self::Pow(unit2, pow2)) if unit1 == unit2 && pow1 > pow2 =>
+                    Self::pow(*unit1, pow1 - pow2),
+                (unit1, unit2) => Self::Pro(Box::new(unit1), Box::new(unit2)),
+            },
+            Self::Pow(unit, pow) => Self::Pow(Box::new(unit.reduce()), pow),
+            unit => unit,
+        }
+    }
 }
 macro_rules! unit_per {
     ($v1:expr, $v2:expr) => {
-        self::UnitKind::Per(Box::new($v1), Box::new($v2))
+        $crate::UnitKind::Per(Box::new($v1), Box::new($v2))
     };
 }
 macro_rules! unit_pro {
     ($v1:expr, $v2:expr) => {
-        self::UnitKind::Pro(Box::new($v1), Box::new($v2))
+        $crate::UnitKind::Pro(Box::new($v1), Box::new($v2))
     };
 }
 macro_rules! unit_pow {
     ($v1:expr, $v2:expr) => {
-        self::UnitKind::Pow(Box::new($v1), $v2)
+        $crate::UnitKind::Pow(Box::new($v1), $v2)
     };
 }
 macro_rules! native {
     ($id:ident) => {
-        self::UnitKind::Native(NativeUnit::$id)
+        $crate::UnitKind::Native($crate::NativeUnit::$id)
+    };
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+// the numbers any value type that can be multiplied by them is scaled by, vectors included,
+// sealed so it never covers Unit itself or the integer scalars implemented per value type
+pub trait Scalar: sealed::Sealed {}
+macro_rules! scalar {
+    ($($t:ty),*) => {
+        $(
+            impl sealed::Sealed for $t {}
+            impl Scalar for $t {}
+        )*
     };
 }
+scalar!(isize, f32, f64);
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Unit<T> {
     value: T,
     unit: UnitKind
@@ -95,6 +631,62 @@ impl<T> Unit<T> {
     pub fn unit_mut(&mut self) -> &mut UnitKind {
         &mut self.unit
     }
+    pub fn canonicalize(self) -> Self {
+        Self::new(self.value, self.unit.canonicalize())
+    }
+    pub fn simplify(self) -> Self {
+        Self::new(self.value, self.unit.simplify())
+    }
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Unit<U> {
+        Unit::new(f(self.value), self.unit)
+    }
+    pub fn try_into_native(self) -> Option<(T, NativeUnit)> {
+        match self.unit {
+            UnitKind::Native(unit) => Some((self.value, unit)),
+            _ => None,
+        }
+    }
+    pub fn as_ratio_unit(self) -> Option<Self> {
+        match self.unit.reduce() {
+            UnitKind::None => Some(Self::new(self.value, UnitKind::None)),
+            _ => None,
+        }
+    }
+    pub fn name_of_dimension(&self) -> Option<&'static str> {
+        let exponents = self.unit.base_exponents()?;
+        NAMED_DIMENSIONS.into_iter()
+            .find(|(_, pattern)| exponents.iter().map(|(dimension, pow)| (*dimension, *pow)).eq(pattern.iter().copied()))
+            .map(|(name, _)| name)
+    }
+    // like "L T^-1", "1" when dimensionless and "?" when custom units are involved
+    pub fn dimension_string(&self) -> String {
+        let Some(exponents) = self.unit.base_exponents() else {
+            return "?".to_string()
+        };
+        if exponents.is_empty() {
+            return "1".to_string()
+        }
+        exponents.into_iter()
+            .map(|(dimension, pow)| match pow {
+                1 => dimension.symbol().to_string(),
+                pow => format!("{}^{pow}", dimension.symbol()),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+    pub fn expect_dimension(self, dimension: BaseDimension) -> Result<Self, WrongDimension> {
+        match self.unit.native_factor() {
+            Some((unit, _)) if unit.dimension() == dimension => Ok(self),
+            Some((unit, _)) => Err(WrongDimension { expected: dimension, found: Some(unit.dimension()) }),
+            None => Err(WrongDimension { expected: dimension, found: None }),
+        }
+    }
+    pub fn is_compatible_with(&self, other: &Self) -> bool {
+        match (self.unit.native_factor(), other.unit.native_factor()) {
+            (Some((unit1, _)), Some((unit2, _))) => unit1.dimension() == unit2.dimension(),
+            _ => self.unit == other.unit,
+        }
+    }
 }
 impl Unit<f64> {
     pub fn powf(self, n: f64) -> Self {
@@ -103,6 +695,334 @@ impl Unit<f64> {
     pub fn powi(self, n: i32) -> Self {
         Self::new(self.value.powi(n), self.unit)
     }
+    pub fn round(self) -> Self {
+        Self::new(self.value.round(), self.unit)
+    }
+    pub fn floor(self) -> Self {
+        Self::new(self.value.floor(), self.unit)
+    }
+    pub fn ceil(self) -> Self {
+        Self::new(self.value.ceil(), self.unit)
+    }
+    pub fn trunc(self) -> Self {
+        Self::new(self.value.trunc(), self.unit)
+    }
+    pub fn abs(self) -> Self {
+        Self::new(self.value.abs(), self.unit)
+    }
+    pub fn to_base_with_factor(&self) -> Option<(Self, f64)> {
+        let (unit, factor) = self.unit.native_factor()?;
+        Some((Self::new(self.value * factor, UnitKind::Native(unit.dimension().base_unit())), factor))
+    }
+    // inverse of to_value_in_base, the display unit has to be of the given dimension
+    pub fn from_base_quantity(value: f64, dimension: BaseDimension, unit: UnitKind) -> Result<Self, WrongDimension> {
+        let Some((native, factor)) = unit.native_factor() else {
+            return Err(WrongDimension { expected: dimension, found: None })
+        };
+        if native.dimension() != dimension {
+            return Err(WrongDimension { expected: dimension, found: Some(native.dimension()) })
+        }
+        Ok(Self::new(value / factor, unit))
+    }
+    pub fn to_value_in_base(&self) -> f64 {
+        match self.to_base_with_factor() {
+            Some((base, _)) => base.value,
+            None => panic!("{} has no single base unit", self.unit),
+        }
+    }
+    pub fn to_unit_system(&self, system: UnitSystem) -> Option<Self> {
+        let (unit, unit_factor) = self.unit.native_factor()?;
+        let (target, factor) = system.unit_for(unit.dimension());
+        Some(Self::new(self.value * unit_factor / factor, target))
+    }
+    // fuel economy as distance per volume, from either a l/100km or a mpg style unit
+    fn meters_per_liter(&self) -> Option<f64> {
+        fn factor(unit: &UnitKind) -> Option<(BaseDimension, f64)> {
+            match unit {
+                UnitKind::Custom(name) if name == "gal" => Some((BaseDimension::Volume, US_GALLON)),
+                UnitKind::Custom(name) if name == "mi" => Some((BaseDimension::Length, MILE)),
+                UnitKind::Custom(name) if name == "100km" => Some((BaseDimension::Length, 100000.)),
+                unit => unit.native_factor().map(|(unit, factor)| (unit.dimension(), factor)),
+            }
+        }
+        let UnitKind::Pro(top, bottom) = &self.unit else {
+            return None
+        };
+        match (factor(top)?, factor(bottom)?) {
+            ((BaseDimension::Length, length), (BaseDimension::Volume, volume)) =>
+                Some(self.value * length / volume),
+            ((BaseDimension::Volume, volume), (BaseDimension::Length, length)) =>
+                Some(length / (self.value * volume)),
+            _ => None,
+        }
+    }
+    pub fn to_mpg(&self) -> Option<Self> {
+        Some(Self::new(
+            self.meters_per_liter()? * US_GALLON / MILE,
+            UnitKind::Pro(Box::new(UnitKind::Custom("mi".to_string())), Box::new(UnitKind::Custom("gal".to_string())))
+        ))
+    }
+    pub fn to_l_per_100km(&self) -> Option<Self> {
+        Some(Self::new(
+            100000. / self.meters_per_liter()?,
+            UnitKind::Pro(Box::new(UnitKind::Native(NativeUnit::Liter)), Box::new(UnitKind::Custom("100km".to_string())))
+        ))
+    }
+    pub fn is_approx_integer(&self, epsilon: f64) -> bool {
+        (self.value - self.value.round()).abs() <= epsilon
+    }
+    fn root(self, degree: usize, value: f64) -> Result<Self, UnitError> {
+        match self.unit.clone().pow_rational(1, degree as i64) {
+            Some(unit) => Ok(Self::new(value, unit)),
+            None => Err(UnitError::NoExactRoot { unit: self.unit, degree }),
+        }
+    }
+    // errors on negative values instead of a silent NaN, and on units that aren't squares
+    pub fn sqrt(self) -> Result<Self, UnitError> {
+        if self.value < 0. {
+            return Err(UnitError::NegativeRoot)
+        }
+        let value = self.value.sqrt();
+        self.root(2, value)
+    }
+    pub fn cbrt(self) -> Result<Self, UnitError> {
+        let value = self.value.cbrt();
+        self.root(3, value)
+    }
+    pub fn try_pow_rational(self, num: i32, den: u32) -> Option<Self> {
+        if den == 0 {
+            return None
+        }
+        let unit = self.unit.pow_rational(num as i64, den as i64)?;
+        Some(Self::new(self.value.powf(num as f64 / den as f64), unit))
+    }
+    pub fn convert_to_custom(self, name: &str, factor: f64) -> Self {
+        Self::new(self.value * factor, UnitKind::Custom(name.to_string()))
+    }
+    pub fn from_ppm(ppm: f64) -> Self {
+        Self::new(ppm / 1e6, UnitKind::None)
+    }
+    pub fn to_ppm(&self) -> Option<f64> {
+        (self.unit == UnitKind::None).then_some(self.value * 1e6)
+    }
+    // the prefix (in steps of a thousand) that brings the value into 1..1000
+    pub fn nearest_named_prefix(&self) -> Option<SiPrefix> {
+        let magnitude = self.value.abs();
+        if (1. ..1000.).contains(&magnitude) || magnitude == 0. || !magnitude.is_finite() {
+            return None
+        }
+        SiPrefix::ALL.into_iter()
+            .filter(|prefix| *prefix != SiPrefix::Centi)
+            .find(|prefix| magnitude >= prefix.factor())
+            .or(Some(SiPrefix::Nano))
+    }
+    // splits into (value, factor, base unit) with value * factor in the base unit,
+    // the factor is the named prefix picked like nearest_named_prefix or 1
+    pub fn scale_unit_value_separately(&self) -> Option<(f64, f64, NativeUnit)> {
+        let (base, _) = self.to_base_with_factor()?;
+        let UnitKind::Native(unit) = base.unit else {
+            return None
+        };
+        let factor = base.nearest_named_prefix().map_or(1., |prefix| prefix.factor());
+        Some((base.value / factor, factor, unit))
+    }
+    // converts and puts a prefix picked like nearest_named_prefix on the result,
+    // prefixes only go on the base units (m, l, g, s)
+    pub fn convert_preserving_prefix(&self, target: NativeUnit) -> Option<Self> {
+        let converted = self.convert_to(target)?;
+        match converted.nearest_named_prefix() {
+            Some(prefix) if target.dimension().base_unit() == target =>
+                Some(Self::new(converted.value / prefix.factor(), UnitKind::Prefixed(prefix, target))),
+            _ => Some(converted),
+        }
+    }
+    // rounds to a whole amount of the largest unit of the system that keeps the value at or above 1
+    pub fn round_to_nearest_unit(&self, system: UnitSystem) -> Option<Self> {
+        let (unit, factor) = self.unit.native_factor()?;
+        let base_value = self.value * factor;
+        let mut units = system.units_for(unit.dimension());
+        let index = units.iter().rposition(|(_, factor)| base_value.abs() / factor >= 1.).unwrap_or(0);
+        let (target, factor) = units.swap_remove(index);
+        Some(Self::new((base_value / factor).round(), target))
+    }
+    pub fn round_half_even(self, decimals: u32) -> Self {
+        let scale = 10f64.powi(decimals as i32);
+        Self::new((self.value * scale).round_ties_even() / scale, self.unit)
+    }
+    // the value expressed in another unit of the same dimension
+    fn value_in(&self, unit: &UnitKind) -> Option<f64> {
+        match (&self.unit, unit) {
+            (unit1, unit2) if unit1 == unit2 => Some(self.value),
+            (from, to) => {
+                let ((from, from_factor), (to, to_factor)) = (from.native_factor()?, to.native_factor()?);
+                (from.dimension() == to.dimension()).then(|| self.value * from_factor / to_factor)
+            }
+        }
+    }
+    // strips the prefix, other units stay as they are
+    pub fn to_base(self) -> Self {
+        match self.unit {
+            UnitKind::Prefixed(prefix, unit) => Self::new(self.value * prefix.factor(), UnitKind::Native(unit)),
+            unit => Self::new(self.value, unit),
+        }
+    }
+    pub fn convert_to(&self, target: NativeUnit) -> Option<Self> {
+        let target = UnitKind::Native(target);
+        Some(Self::new(self.value_in(&target)?, target))
+    }
+    pub fn convert(self, target: NativeUnit) -> Result<Self, UnitError> {
+        let target = UnitKind::Native(target);
+        match self.value_in(&target) {
+            Some(value) => Ok(Self::new(value, target)),
+            None => Err(UnitError::Incompatible { from: self.unit, to: target }),
+        }
+    }
+    // converts directly between each pair of scales so round trips don't pick up error via kelvin
+    pub fn convert_temperature(self, target: TempScale) -> Result<Self, UnitError> {
+        let Some(scale) = TempScale::of(&self.unit) else {
+            return Err(UnitError::Incompatible { from: self.unit, to: target.unit() })
+        };
+        let value = match (scale, target) {
+            (TempScale::Celsius, TempScale::Kelvin) => self.value + 273.15,
+            (TempScale::Kelvin, TempScale::Celsius) => self.value - 273.15,
+            (TempScale::Fahrenheit, TempScale::Celsius) => (self.value - 32.) * 5. / 9.,
+            (TempScale::Celsius, TempScale::Fahrenheit) => self.value * 9. / 5. + 32.,
+            (TempScale::Fahrenheit, TempScale::Kelvin) => (self.value - 32.) * 5. / 9. + 273.15,
+            (TempScale::Kelvin, TempScale::Fahrenheit) => (self.value - 273.15) * 9. / 5. + 32.,
+            _ => self.value,
+        };
+        Ok(Self::new(value, target.unit()))
+    }
+    // negative, NaN and too large values error instead of saturating
+    pub fn to_duration(&self) -> Result<Duration, UnitError> {
+        let second = UnitKind::Native(NativeUnit::Second);
+        let Some(seconds) = self.value_in(&second) else {
+            return Err(UnitError::Incompatible { from: self.unit.clone(), to: second })
+        };
+        Duration::try_from_secs_f64(seconds).map_err(|_| UnitError::InvalidDuration { seconds })
+    }
+    pub fn from_duration(duration: Duration, target: NativeUnit) -> Result<Self, UnitError> {
+        Unit::new(duration.as_secs_f64(), UnitKind::Native(NativeUnit::Second)).convert(target)
+    }
+    // converts through every step in order, failing if any hop changes dimension
+    pub fn checked_convert_chain(&self, steps: &[NativeUnit]) -> Option<Self> {
+        let (mut current, factor) = self.unit.native_factor()?;
+        for step in steps {
+            if step.dimension() != current.dimension() {
+                return None
+            }
+            current = *step;
+        }
+        Some(Self::new(self.value * factor / current.factor(), UnitKind::Native(current)))
+    }
+    pub fn assert_positive(self) -> Result<PositiveUnit, Self> {
+        PositiveUnit::try_from(self)
+    }
+    pub fn display(&self) -> UnitDisplay<'_> {
+        UnitDisplay { unit: self, precision: None, space: false, unicode: false, system: None }
+    }
+    pub fn display_rounded(self, decimals: usize) -> Rounded {
+        Rounded(self, decimals)
+    }
+    pub fn percentage_change(&self, baseline: &Self) -> Option<f64> {
+        if self.unit != baseline.unit || baseline.value == 0. {
+            return None
+        }
+        Some((self.value - baseline.value) / baseline.value * 100.)
+    }
+    pub fn recip(self) -> Self {
+        Self::new(self.value.recip(), self.unit.recip())
+    }
+    // other is converted into this unit when it's a rescaled native, compounds have to cancel to the same unit
+    pub fn approx_eq(&self, other: &Self, epsilon: f64) -> bool {
+        let value = match other.value_in(&self.unit) {
+            Some(value) => value,
+            None if self.unit.clone().simplify() == other.unit.clone().simplify() => other.value,
+            None => return false,
+        };
+        (self.value - value).abs() <= epsilon
+    }
+    // 1/value, keeping the unit as it is (unlike a reciprocal)
+    pub fn invert_value(self) -> Option<Self> {
+        if self.value == 0. {
+            return None
+        }
+        Some(Self::new(1. / self.value, self.unit))
+    }
+    pub fn compare_report(&self, other: &Self) -> String {
+        let Some(value) = other.value_in(&self.unit) else {
+            return "incomparable (different dimensions)".to_string()
+        };
+        match self.value.partial_cmp(&value) {
+            Some(Ordering::Less) => format!("{self} is less than {other}"),
+            Some(Ordering::Equal) => format!("{self} is equal to {other}"),
+            Some(Ordering::Greater) => format!("{self} is greater than {other}"),
+            None => "incomparable (not a number)".to_string(),
+        }
+    }
+    // index of the bin the value falls into, given ascending thresholds
+    pub fn split_at_unit_boundary(&self, thresholds: &[Self]) -> Option<usize> {
+        let mut bin = 0;
+        for threshold in thresholds {
+            if self.value >= threshold.value_in(&self.unit)? {
+                bin += 1;
+            }
+        }
+        Some(bin)
+    }
+    // the bool tells whether the value was out of range
+    pub fn clamp_reporting(self, min: Self, max: Self) -> (Self, bool) {
+        let (Some(low), Some(high)) = (min.value_in(&self.unit), max.value_in(&self.unit)) else {
+            panic!("cannot clamp {} between {} and {}", self.unit, min.unit, max.unit)
+        };
+        if self.value < low {
+            (Self::new(low, self.unit), true)
+        } else if self.value > high {
+            (Self::new(high, self.unit), true)
+        } else {
+            (self, false)
+        }
+    }
+    pub fn clamp_nonneg_converting(self, max: Self) -> Self {
+        let zero = Self::new(0., self.unit.clone());
+        self.clamp_reporting(zero, max).0
+    }
+    // trailing zeros of whole numbers are not counted as significant
+    pub fn significant_digits(&self) -> u32 {
+        if !self.value.is_finite() {
+            return 0
+        }
+        let repr = self.value.abs().to_string();
+        let digits = match repr.split_once('.') {
+            Some((whole, fraction)) => format!("{whole}{fraction}").trim_start_matches('0').len(),
+            None => repr.trim_matches('0').len(),
+        };
+        digits.max(1) as u32
+    }
+    pub fn abs_diff(&self, other: &Self) -> Option<Self> {
+        Some(Self::new((self.value - other.value_in(&self.unit)?).abs(), self.unit.clone()))
+    }
+    // adds two compatible quantities, expressed in the preferred one of their units
+    pub fn combine(self, other: Self, prefer: UnitPreference) -> Option<Self> {
+        let left_is_larger = match (self.unit.native_factor(), other.unit.native_factor()) {
+            (Some((_, factor1)), Some((_, factor2))) => factor1 >= factor2,
+            _ => true,
+        };
+        let keep_left = match prefer {
+            UnitPreference::Larger => left_is_larger,
+            UnitPreference::Smaller => !left_is_larger,
+            UnitPreference::Left => true,
+            UnitPreference::Right => false,
+        };
+        let (kept, converted) = if keep_left { (self, other) } else { (other, self) };
+        Some(Self::new(kept.value + converted.value_in(&kept.unit)?, kept.unit))
+    }
+    pub fn midpoint(&self, other: &Self) -> Self {
+        if self.unit != other.unit {
+            panic!("cannot take the midpoint of {} and {}", self.unit, other.unit)
+        }
+        Self::new(self.value.midpoint(other.value), self.unit.clone())
+    }
 }
 impl Unit<f32> {
     pub fn powf(self, n: f32) -> Self {
@@ -111,20 +1031,160 @@ impl Unit<f32> {
     pub fn powi(self, n: i32) -> Self {
         Self::new(self.value.powi(n), self.unit)
     }
+    pub fn round(self) -> Self {
+        Self::new(self.value.round(), self.unit)
+    }
+    pub fn floor(self) -> Self {
+        Self::new(self.value.floor(), self.unit)
+    }
+    pub fn ceil(self) -> Self {
+        Self::new(self.value.ceil(), self.unit)
+    }
+    pub fn trunc(self) -> Self {
+        Self::new(self.value.trunc(), self.unit)
+    }
+    pub fn abs(self) -> Self {
+        Self::new(self.value.abs(), self.unit)
+    }
+    pub fn recip(self) -> Self {
+        Self::new(self.value.recip(), self.unit.recip())
+    }
+    pub fn approx_eq(&self, other: &Self, epsilon: f32) -> bool {
+        Unit::<f64>::from(self.clone()).approx_eq(&other.clone().into(), epsilon.into())
+    }
+}
+// arithmetic on the std duration itself goes through the generic operator impls
+impl Unit<Duration> {
+    pub fn to_seconds_unit(&self) -> Unit<f64> {
+        Unit::new(self.value.as_secs_f64(), UnitKind::Native(NativeUnit::Second))
+    }
 }
 impl Unit<i64> {
     pub fn powf(self, n: u32) -> Self {
         Self::new(self.value.pow(n), self.unit)
     }
+    pub fn abs(self) -> Self {
+        Self::new(self.value.abs(), self.unit)
+    }
 }
 impl Unit<i32> {
     pub fn powf(self, n: u32) -> Self {
         Self::new(self.value.pow(n), self.unit)
     }
+    pub fn abs(self) -> Self {
+        Self::new(self.value.abs(), self.unit)
+    }
+}
+// fallible arithmetic, floats error on inf and NaN results
+macro_rules! try_ops_float {
+    ($($t:ty),*) => {
+        $(
+            impl Unit<$t> {
+                fn finite(self) -> Result<Self, UnitError> {
+                    if self.value.is_finite() {
+                        Ok(self)
+                    } else {
+                        Err(UnitError::NotFinite)
+                    }
+                }
+                pub fn try_add(self, rhs: Self) -> Result<Self, UnitError> {
+                    self.checked_add(rhs)?.finite()
+                }
+                pub fn try_sub(self, rhs: Self) -> Result<Self, UnitError> {
+                    self.checked_sub(rhs)?.finite()
+                }
+                pub fn try_mul(self, rhs: Self) -> Result<Self, UnitError> {
+                    (self * rhs).finite()
+                }
+                pub fn try_div(self, rhs: Self) -> Result<Self, UnitError> {
+                    (self / rhs).finite()
+                }
+            }
+        )*
+    };
+}
+try_ops_float!(f64, f32);
+// fallible arithmetic, integers error on overflow and division by zero
+macro_rules! try_ops_int {
+    ($($t:ty),*) => {
+        $(
+            impl Unit<$t> {
+                pub fn try_add(self, rhs: Self) -> Result<Self, UnitError> {
+                    self.same_unit(&rhs)?;
+                    Ok(Self::new(self.value.checked_add(rhs.value).ok_or(UnitError::Overflow)?, self.unit))
+                }
+                pub fn try_sub(self, rhs: Self) -> Result<Self, UnitError> {
+                    self.same_unit(&rhs)?;
+                    Ok(Self::new(self.value.checked_sub(rhs.value).ok_or(UnitError::Overflow)?, self.unit))
+                }
+                pub fn try_mul(self, rhs: Self) -> Result<Self, UnitError> {
+                    let value = self.value.checked_mul(rhs.value).ok_or(UnitError::Overflow)?;
+                    Ok(Self::new(value, self.unit.times(rhs.unit)))
+                }
+                pub fn try_div(self, rhs: Self) -> Result<Self, UnitError> {
+                    if rhs.value == 0 {
+                        return Err(UnitError::DivByZero)
+                    }
+                    let value = self.value.checked_div(rhs.value).ok_or(UnitError::Overflow)?;
+                    Ok(Self::new(value, self.unit.over(rhs.unit)))
+                }
+            }
+        )*
+    };
+}
+try_ops_int!(i64, i32);
+impl TryFrom<Unit<i64>> for Unit<i32> {
+    type Error = UnitError;
+    fn try_from(unit: Unit<i64>) -> Result<Self, Self::Error> {
+        Ok(Self::new(unit.value.try_into().map_err(|_| UnitError::OutOfRange)?, unit.unit))
+    }
+}
+impl From<Unit<i32>> for Unit<i64> {
+    fn from(unit: Unit<i32>) -> Self {
+        unit.map(i64::from)
+    }
+}
+impl From<Unit<i32>> for Unit<f64> {
+    fn from(unit: Unit<i32>) -> Self {
+        unit.map(f64::from)
+    }
+}
+impl From<Unit<f32>> for Unit<f64> {
+    fn from(unit: Unit<f32>) -> Self {
+        unit.map(f64::from)
+    }
+}
+thread_local! {
+    static DEFAULT_PRECISION: Cell<Option<usize>> = const { Cell::new(None) };
+}
+pub fn set_default_precision(precision: Option<usize>) {
+    DEFAULT_PRECISION.with(|default| default.set(precision))
+}
+pub fn default_precision() -> Option<usize> {
+    DEFAULT_PRECISION.with(Cell::get)
+}
+// the default only applies to float values (and references to them),
+// a precision would cut other values like strings short
+fn default_precision_of<T: 'static>() -> Option<usize> {
+    let floats = [TypeId::of::<f32>(), TypeId::of::<f64>(), TypeId::of::<&f32>(), TypeId::of::<&f64>()];
+    floats.contains(&TypeId::of::<T>()).then(default_precision).flatten()
 }
-impl<T: Display> Display for Unit<T> {
+// integer values ignore the precision
+impl<T: Display + 'static> Display for Unit<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}{}", self.value, self.unit)
+        match f.precision().or_else(default_precision_of::<T>) {
+            Some(precision) => write!(f, "{:.*}", precision, self.value)?,
+            None => write!(f, "{}", self.value)?,
+        }
+        self.unit.write(f, f.alternate())
+    }
+}
+impl<T: Display + 'static> Unit<T> {
+    pub fn display_without_unit(&self) -> String {
+        match default_precision_of::<T>() {
+            Some(precision) => format!("{:.*}", precision, self.value),
+            None => self.value.to_string(),
+        }
     }
 }
 impl<T: Debug> Debug for Unit<T> {
@@ -137,34 +1197,25 @@ impl<T: PartialEq> PartialEq for Unit<T> {
         self.value == other.value && self.unit == other.unit
     }
 }
+impl<T: Eq> Eq for Unit<T> {}
+impl<T: Hash> Hash for Unit<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+        self.unit.hash(state);
+    }
+}
 impl<T: Clone> Clone for Unit<T> {
     fn clone(&self) -> Self {
         Self::new(self.value.clone(), self.unit.clone())
     }
 }
+// quantities of different units are unordered
 impl<T: PartialOrd> PartialOrd for Unit<T> {
-    fn gt(&self, other: &Self) -> bool {
-        self.value > other.value && self.unit == other.unit
-    }
-    fn lt(&self, other: &Self) -> bool {
-        self.value < other.value && self.unit == other.unit
-    }
-    fn ge(&self, other: &Self) -> bool {
-        self.value >= other.value && self.unit == other.unit
-    }
-    fn le(&self, other: &Self) -> bool {
-        self.value <= other.value && self.unit == other.unit
-    }
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if self.lt(other) {
-            Some(Ordering::Less)
-        } else if self.gt(other) {
-            Some(Ordering::Greater)
-        } else if self.eq(other) {
-            Some(Ordering::Equal)
-        } else {
-            None
+        if self.unit != other.unit {
+            return None
         }
+        self.value.partial_cmp(&other.value)
     }
 }
 impl<T: PartialOrd> Unit<T> {
@@ -183,163 +1234,625 @@ impl<T: PartialOrd> Unit<T> {
         }
     }
 }
+impl<T: Default> Unit<T> {
+    pub fn zero(unit: UnitKind) -> Self {
+        Self::new(T::default(), unit)
+    }
+}
+impl<T: From<u8>> Unit<T> {
+    pub fn one(unit: UnitKind) -> Self {
+        Self::new(T::from(1), unit)
+    }
+}
+impl<T: Default + PartialEq> Unit<T> {
+    pub fn is_zero(&self) -> bool {
+        self.value == T::default()
+    }
+}
+// the identities are dimensionless, so unlike the inherent is_zero the unit has to be None too
+#[cfg(feature = "num-traits")]
+impl<T: num_traits::Zero> num_traits::Zero for Unit<T> {
+    fn zero() -> Self {
+        Self::new(T::zero(), UnitKind::None)
+    }
+    fn is_zero(&self) -> bool {
+        self.value.is_zero() && self.unit == UnitKind::None
+    }
+}
+#[cfg(feature = "num-traits")]
+impl<T: num_traits::One> num_traits::One for Unit<T> {
+    fn one() -> Self {
+        Self::new(T::one(), UnitKind::None)
+    }
+}
+impl<T: Mul<Output = T>> Unit<T> {
+    pub fn checked_mul_capped(self, rhs: Self, max_exp: usize) -> Option<Self> {
+        let product = self * rhs;
+        (product.unit.max_exponent() <= max_exp).then_some(product)
+    }
+}
+impl<T> Unit<T> {
+    // sums and differences need both operands in the same unit,
+    // shared by the checked, try and assigning versions of add and sub
+    fn same_unit(&self, rhs: &Self) -> Result<(), UnitError> {
+        if self.unit != rhs.unit {
+            return Err(UnitError::Mismatch { left: self.unit.clone(), right: rhs.unit.clone() })
+        }
+        Ok(())
+    }
+}
+impl<T: Add<Output = T>> Unit<T> {
+    pub fn checked_add(self, rhs: Self) -> Result<Self, UnitError> {
+        self.same_unit(&rhs)?;
+        Ok(Self::new(self.value + rhs.value, self.unit))
+    }
+}
+impl<T: Sub<Output = T>> Unit<T> {
+    pub fn checked_sub(self, rhs: Self) -> Result<Self, UnitError> {
+        self.same_unit(&rhs)?;
+        Ok(Self::new(self.value - rhs.value, self.unit))
+    }
+}
 impl<T: Add<Output = T>> Add for Unit<T> {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output {
-        if self.unit != rhs.unit {
-            panic!("cannot add {} with {}", self.unit, rhs.unit)
-        }
-        Self::new(self.value + rhs.value, self.unit)
+        self.checked_add(rhs).unwrap_or_else(|err| panic!("cannot add: {err}"))
     }
 }
 impl<T: Sub<Output = T>> Sub for Unit<T> {
     type Output = Self;
     fn sub(self, rhs: Self) -> Self::Output {
-        if self.unit != rhs.unit {
-            panic!("cannot subtract {} with {}", self.unit, rhs.unit)
-        }
-        Self::new(self.value - rhs.value, self.unit)
+        self.checked_sub(rhs).unwrap_or_else(|err| panic!("cannot subtract: {err}"))
     }
 }
 impl<T: Mul<Output = T>> Mul for Unit<T> {
     type Output = Self;
     fn mul(self, rhs: Self) -> Self::Output {
-        match (self.unit, rhs.unit) {
-            (UnitKind::Pow(unit1, pow), unit2) if *unit1 == unit2 =>
-                Self::new(self.value * rhs.value, UnitKind::Pow(unit1, pow + 1)),
-            (UnitKind::Pro(unit1, unit2), unit3) if *unit2 == unit3 =>
-                Self::new(self.value * rhs.value, *unit1),
-            (unit1, unit2) if unit1 == unit2 => Self::new(self.value * rhs.value, UnitKind::Pow(Box::new(unit1), 2)),
-            (unit1, unit2) => Self::new(self.value * rhs.value, UnitKind::Per(Box::new(unit1), Box::new(unit2))),
-        }
+        Self::new(self.value * rhs.value, self.unit.times(rhs.unit))
     }
 }
 impl<T: Div<Output = T>> Div for Unit<T> {
     type Output = Self;
     fn div(self, rhs: Self) -> Self::Output {
-        match (self.unit, rhs.unit) {
-            (UnitKind::Per(unit1, unit2), unit3) if *unit2 == unit3 =>
-                Self::new(self.value / rhs.value, *unit1),
-            (unit1, unit2) if unit1 == unit2 => Self::new(self.value / rhs.value, UnitKind::None),
-            (unit1, unit2) => Self::new(self.value / rhs.value, UnitKind::Pro(Box::new(unit1), Box::new(unit2))),
+        Self::new(self.value / rhs.value, self.unit.over(rhs.unit))
+    }
+}
+impl<T: AddAssign> AddAssign for Unit<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        if let Err(err) = self.same_unit(&rhs) {
+            panic!("cannot add: {err}")
         }
+        self.value += rhs.value;
     }
 }
-impl<T: Mul<isize, Output = T>> Mul<isize> for Unit<T> {
-    type Output = Self;
-    fn mul(self, rhs: isize) -> Self::Output {
-        Self::new(self.value * rhs, self.unit)
+impl<T: SubAssign> SubAssign for Unit<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        if let Err(err) = self.same_unit(&rhs) {
+            panic!("cannot subtract: {err}")
+        }
+        self.value -= rhs.value;
     }
 }
-impl<T: Mul<f64, Output = T>> Mul<f64> for Unit<T> {
-    type Output = Self;
-    fn mul(self, rhs: f64) -> Self::Output {
-        Self::new(self.value * rhs, self.unit)
+impl<T: MulAssign> MulAssign for Unit<T> {
+    fn mul_assign(&mut self, rhs: Self) {
+        self.value *= rhs.value;
+        self.unit = std::mem::replace(&mut self.unit, UnitKind::None).times(rhs.unit);
     }
 }
-impl<T: Mul<f32, Output = T>> Mul<f32> for Unit<T> {
-    type Output = Self;
-    fn mul(self, rhs: f32) -> Self::Output {
-        Self::new(self.value * rhs, self.unit)
+impl<T: DivAssign> DivAssign for Unit<T> {
+    fn div_assign(&mut self, rhs: Self) {
+        self.value /= rhs.value;
+        self.unit = std::mem::replace(&mut self.unit, UnitKind::None).over(rhs.unit);
+    }
+}
+// the first quantity sets the unit, the rest has to match it like for Add
+impl<T: Add<Output = T> + Default> Sum for Unit<T> {
+    fn sum<I: Iterator<Item = Self>>(mut iter: I) -> Self {
+        match iter.next() {
+            Some(first) => iter.fold(first, Add::add),
+            None => Self::new(T::default(), UnitKind::None),
+        }
+    }
+}
+// an empty product is a dimensionless 1
+impl<T: Mul<Output = T> + From<u8>> Product for Unit<T> {
+    fn product<I: Iterator<Item = Self>>(mut iter: I) -> Self {
+        match iter.next() {
+            Some(first) => iter.fold(first, Mul::mul),
+            None => Self::new(T::from(1), UnitKind::None),
+        }
     }
 }
-impl<T: Div<isize, Output = T>> Div<isize> for Unit<T> {
+impl<S: Scalar, T: Mul<S, Output = T>> Mul<S> for Unit<T> {
     type Output = Self;
-    fn div(self, rhs: isize) -> Self::Output {
-        Self::new(self.value / rhs, self.unit)
+    fn mul(self, rhs: S) -> Self::Output {
+        Self::new(self.value * rhs, self.unit)
     }
 }
-impl<T: Div<f64, Output = T>> Div<f64> for Unit<T> {
+impl<S: Scalar, T: Div<S, Output = T>> Div<S> for Unit<T> {
     type Output = Self;
-    fn div(self, rhs: f64) -> Self::Output {
+    fn div(self, rhs: S) -> Self::Output {
         Self::new(self.value / rhs, self.unit)
     }
 }
-impl<T: Div<f32, Output = T>> Div<f32> for Unit<T> {
+impl<S: Scalar, T: Rem<S, Output = T>> Rem<S> for Unit<T> {
     type Output = Self;
-    fn div(self, rhs: f32) -> Self::Output {
-        Self::new(self.value / rhs, self.unit)
+    fn rem(self, rhs: S) -> Self::Output {
+        Self::new(self.value % rhs, self.unit)
     }
 }
+// the other integer scalars are cast into float values, so meter!(2.) * 3u32 works
+macro_rules! float_int_scalar {
+    ($($t:ty),* => $s:tt) => {
+        $( float_int_scalar!(@impl $t, $s); )*
+    };
+    (@impl $t:ty, [$($s:ty),*]) => {
+        $(
+            impl Mul<$s> for Unit<$t> {
+                type Output = Self;
+                fn mul(self, rhs: $s) -> Self::Output {
+                    Self::new(self.value * rhs as $t, self.unit)
+                }
+            }
+            impl Div<$s> for Unit<$t> {
+                type Output = Self;
+                fn div(self, rhs: $s) -> Self::Output {
+                    Self::new(self.value / rhs as $t, self.unit)
+                }
+            }
+            impl Rem<$s> for Unit<$t> {
+                type Output = Self;
+                fn rem(self, rhs: $s) -> Self::Output {
+                    Self::new(self.value % rhs as $t, self.unit)
+                }
+            }
+        )*
+    };
+}
+float_int_scalar!(f32, f64 => [i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, usize]);
+// integer values are scaled in i128, so only a result that doesn't fit the value type overflows
+macro_rules! int_scalar {
+    ($($t:ty),* => $s:tt) => {
+        $( int_scalar!(@impl $t, $s); )*
+    };
+    (@impl $t:ty, [$($s:ty),*]) => {
+        $(
+            impl Mul<$s> for Unit<$t> {
+                type Output = Self;
+                fn mul(self, rhs: $s) -> Self::Output {
+                    let value = (self.value as i128).checked_mul(rhs as i128).and_then(|value| value.try_into().ok());
+                    Self::new(value.expect("attempt to multiply with overflow"), self.unit)
+                }
+            }
+            impl Div<$s> for Unit<$t> {
+                type Output = Self;
+                fn div(self, rhs: $s) -> Self::Output {
+                    let value = (self.value as i128 / rhs as i128).try_into();
+                    Self::new(value.expect("attempt to divide with overflow"), self.unit)
+                }
+            }
+            impl Rem<$s> for Unit<$t> {
+                type Output = Self;
+                fn rem(self, rhs: $s) -> Self::Output {
+                    let value = (self.value as i128 % rhs as i128).try_into();
+                    Self::new(value.expect("attempt to calculate the remainder with overflow"), self.unit)
+                }
+            }
+        )*
+    };
+}
+int_scalar!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize => [i8, i16, i32, i64, u8, u16, u32, u64, usize]);
+int_scalar!(i128 => [i128]);
 impl<T: Neg<Output = T>> Neg for Unit<T> {
     type Output = Self;
     fn neg(self) -> Self::Output {
         Self::new(-self.value, self.unit)
     }
 }
+impl<T: Clone + Add<Output = T>> Add<&Unit<T>> for &Unit<T> {
+    type Output = Unit<T>;
+    fn add(self, rhs: &Unit<T>) -> Self::Output {
+        self.clone() + rhs.clone()
+    }
+}
+impl<T: Clone + Sub<Output = T>> Sub<&Unit<T>> for &Unit<T> {
+    type Output = Unit<T>;
+    fn sub(self, rhs: &Unit<T>) -> Self::Output {
+        self.clone() - rhs.clone()
+    }
+}
+impl<T: Clone + Mul<Output = T>> Mul<&Unit<T>> for &Unit<T> {
+    type Output = Unit<T>;
+    fn mul(self, rhs: &Unit<T>) -> Self::Output {
+        self.clone() * rhs.clone()
+    }
+}
+impl<T: Clone + Div<Output = T>> Div<&Unit<T>> for &Unit<T> {
+    type Output = Unit<T>;
+    fn div(self, rhs: &Unit<T>) -> Self::Output {
+        self.clone() / rhs.clone()
+    }
+}
+impl<T: Clone + Neg<Output = T>> Neg for &Unit<T> {
+    type Output = Unit<T>;
+    fn neg(self) -> Self::Output {
+        -self.clone()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnitError {
+    Mismatch { left: UnitKind, right: UnitKind },
+    Incompatible { from: UnitKind, to: UnitKind },
+    NoExactRoot { unit: UnitKind, degree: usize },
+    NegativeRoot,
+    InvalidDuration { seconds: f64 },
+    NotFinite,
+    DivByZero,
+    Overflow,
+    CyclicUnit(String),
+    OutOfRange,
+}
+impl Display for UnitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Mismatch { left, right } => write!(f, "mismatched units {left} and {right}"),
+            Self::Incompatible { from, to } => write!(f, "cannot convert {from} into {to}"),
+            Self::NoExactRoot { unit, degree } => write!(f, "{unit} has no root of degree {degree}"),
+            Self::NegativeRoot => write!(f, "cannot take the square root of a negative value"),
+            Self::InvalidDuration { seconds } => write!(f, "{seconds}s is not representable as a duration"),
+            Self::NotFinite => write!(f, "result is not finite"),
+            Self::DivByZero => write!(f, "division by zero"),
+            Self::Overflow => write!(f, "arithmetic overflow"),
+            Self::CyclicUnit(name) => write!(f, "custom unit {name} is defined in terms of itself"),
+            Self::OutOfRange => write!(f, "value is out of range for the target type"),
+        }
+    }
+}
+impl std::error::Error for UnitError {}
+
+// found is None when the unit isn't a single native unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WrongDimension {
+    pub expected: BaseDimension,
+    pub found: Option<BaseDimension>
+}
+impl Display for WrongDimension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.found {
+            Some(found) => write!(f, "expected a {:?} quantity, found {:?}", self.expected, found),
+            None => write!(f, "expected a {:?} quantity, found a compound or custom unit", self.expected),
+        }
+    }
+}
+impl std::error::Error for WrongDimension {}
+
+// a quantity guaranteed to not be negative, rejected values are handed back
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositiveUnit(Unit<f64>);
+impl PositiveUnit {
+    pub fn get(&self) -> &Unit<f64> {
+        &self.0
+    }
+    pub fn into_inner(self) -> Unit<f64> {
+        self.0
+    }
+}
+impl TryFrom<Unit<f64>> for PositiveUnit {
+    type Error = Unit<f64>;
+    fn try_from(unit: Unit<f64>) -> Result<Self, Self::Error> {
+        if unit.value >= 0. {
+            Ok(Self(unit))
+        } else {
+            Err(unit)
+        }
+    }
+}
+impl Display for PositiveUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// configurable rendering, the precision falls back to the default precision
+pub struct UnitDisplay<'a> {
+    unit: &'a Unit<f64>,
+    precision: Option<usize>,
+    space: bool,
+    unicode: bool,
+    system: Option<UnitSystem>
+}
+impl UnitDisplay<'_> {
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = Some(precision);
+        self
+    }
+    pub fn with_space(mut self, space: bool) -> Self {
+        self.space = space;
+        self
+    }
+    pub fn unicode(mut self, unicode: bool) -> Self {
+        self.unicode = unicode;
+        self
+    }
+    // quantities without a unit in the system are rendered as they are
+    pub fn system(mut self, system: UnitSystem) -> Self {
+        self.system = Some(system);
+        self
+    }
+}
+impl Display for UnitDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let unit = self.system
+            .and_then(|system| self.unit.to_unit_system(system))
+            .unwrap_or_else(|| self.unit.clone());
+        match self.precision.or_else(default_precision) {
+            Some(precision) => write!(f, "{:.*}", precision, unit.value)?,
+            None => write!(f, "{}", unit.value)?,
+        }
+        if self.space && unit.unit != UnitKind::None {
+            write!(f, " ")?;
+        }
+        unit.unit.write(f, self.unicode)
+    }
+}
+pub struct Rounded(pub Unit<f64>, pub usize);
+impl Display for Rounded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.*}{}", self.1, self.0.value, self.0.unit)
+    }
+}
+// renders a dimensionless ratio in parts per million, so it can only be built from UnitKind::None quantities
+pub struct Ppm(Unit<f64>);
+impl Ppm {
+    pub fn get(&self) -> &Unit<f64> {
+        &self.0
+    }
+    pub fn into_inner(self) -> Unit<f64> {
+        self.0
+    }
+}
+impl TryFrom<Unit<f64>> for Ppm {
+    type Error = Unit<f64>;
+    fn try_from(unit: Unit<f64>) -> Result<Self, Self::Error> {
+        if unit.unit == UnitKind::None {
+            Ok(Self(unit))
+        } else {
+            Err(unit)
+        }
+    }
+}
+impl Display for Ppm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}ppm", self.0.value * 1e6)
+    }
+}
+
+// custom unit names mapped to a factor and the unit they stand for,
+// which may be made up of other registered custom units
+#[derive(Debug, Clone, Default)]
+pub struct UnitRegistry {
+    units: HashMap<String, (f64, UnitKind)>
+}
+impl UnitRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn register(&mut self, name: &str, factor: f64, base: UnitKind) {
+        self.units.insert(name.to_string(), (factor, base));
+    }
+    pub fn expand(&self, unit: &UnitKind) -> Result<UnitKind, UnitError> {
+        Ok(self.expand_with_factor(unit, &mut vec![])?.1)
+    }
+    fn expand_with_factor(&self, unit: &UnitKind, visiting: &mut Vec<String>) -> Result<(f64, UnitKind), UnitError> {
+        Ok(match unit {
+            UnitKind::Custom(name) => match self.units.get(name) {
+                Some(_) if visiting.contains(name) => return Err(UnitError::CyclicUnit(name.clone())),
+                Some((factor, base)) => {
+                    visiting.push(name.clone());
+                    let (inner, base) = self.expand_with_factor(base, visiting)?;
+                    visiting.pop();
+                    (factor * inner, base)
+                }
+                None => (1., unit.clone()),
+            },
+            UnitKind::Pro(unit1, unit2) => {
+                let ((factor1, unit1), (factor2, unit2)) =
+                    (self.expand_with_factor(unit1, visiting)?, self.expand_with_factor(unit2, visiting)?);
+                (factor1 / factor2, UnitKind::Pro(Box::new(unit1), Box::new(unit2)))
+            }
+            UnitKind::Per(unit1, unit2) => {
+                let ((factor1, unit1), (factor2, unit2)) =
+                    (self.expand_with_factor(unit1, visiting)?, self.expand_with_factor(unit2, visiting)?);
+                (factor1 * factor2, UnitKind::Per(Box::new(unit1), Box::new(unit2)))
+            }
+            UnitKind::Pow(unit, pow) => {
+                let (factor, unit) = self.expand_with_factor(unit, visiting)?;
+                (factor.powi(*pow as i32), UnitKind::Pow(Box::new(unit), *pow))
+            }
+            unit => (1., unit.clone()),
+        })
+    }
+}
+impl Unit<f64> {
+    pub fn expand_with(self, registry: &UnitRegistry) -> Result<Self, UnitError> {
+        let (factor, unit) = registry.expand_with_factor(&self.unit, &mut vec![])?;
+        Ok(Self::new(self.value * factor, unit))
+    }
+}
+
+pub fn common_unit(items: &[Unit<f64>]) -> Option<NativeUnit> {
+    let mut smallest = f64::INFINITY;
+    let mut dimension = None;
+    for item in items {
+        let (unit, factor) = item.unit.native_factor()?;
+        if *dimension.get_or_insert(unit.dimension()) != unit.dimension() {
+            return None
+        }
+        smallest = smallest.min((item.value * factor).abs());
+    }
+    let dimension = dimension?;
+    // the largest unit that still keeps every value at or above 1
+    NativeUnit::ALL.into_iter()
+        .filter(|unit| unit.dimension() == dimension)
+        .rev()
+        .find(|unit| smallest / unit.factor() >= 1.)
+        .or(Some(dimension.base_unit()))
+}
+// compound and custom units are left untouched
+pub fn normalize_to_base(items: &mut [Unit<f64>]) {
+    for item in items {
+        if let Some((base, _)) = item.to_base_with_factor() {
+            *item = base;
+        }
+    }
+}
+// geometric mean of dimensionless positive rates
+pub fn geomean_rate(rates: &[Unit<f64>]) -> Option<Unit<f64>> {
+    if rates.is_empty() || rates.iter().any(|rate| rate.unit != UnitKind::None || rate.value <= 0.) {
+        return None
+    }
+    let log_sum: f64 = rates.iter().map(|rate| rate.value.ln()).sum();
+    Some(Unit::new((log_sum / rates.len() as f64).exp(), UnitKind::None))
+}
+pub fn weighted_mean(items: &[(Unit<f64>, f64)]) -> Option<Unit<f64>> {
+    let (first, _) = items.first()?;
+    if items.iter().any(|(item, _)| item.unit != first.unit) {
+        return None
+    }
+    let total_weight: f64 = items.iter().map(|(_, weight)| weight).sum();
+    if total_weight == 0. {
+        return None
+    }
+    let weighted_sum: f64 = items.iter().map(|(item, weight)| item.value * weight).sum();
+    Some(Unit::new(weighted_sum / total_weight, first.unit.clone()))
+}
+pub fn product(items: impl IntoIterator<Item = Unit<f64>>) -> Unit<f64> {
+    items.into_iter().product()
+}
+// sums rate * dt over the samples as a base quantity, so km/h and m/s rates can be mixed,
+// a numerator without a single native unit is kept as it is
+pub fn accumulate_over_time_series(samples: &[(Unit<f64>, Unit<f64>)]) -> Option<Unit<f64>> {
+    let second = UnitKind::Native(NativeUnit::Second);
+    let mut total: Option<Unit<f64>> = None;
+    for (rate, dt) in samples {
+        let UnitKind::Pro(quantity, per) = &rate.unit else {
+            return None
+        };
+        let per_seconds = Unit::new(1., (**per).clone()).value_in(&second)?;
+        let step = Unit::new(rate.value * dt.value_in(&second)? / per_seconds, (**quantity).clone());
+        let step = step.to_base_with_factor().map_or(step, |(base, _)| base);
+        total = match total {
+            Some(total) if total.unit != step.unit => return None,
+            Some(total) => Some(total + step),
+            None => Some(step),
+        };
+    }
+    total
+}
+pub fn convert_all(
+    items: impl IntoIterator<Item = Unit<f64>>,
+    target: NativeUnit
+) -> impl Iterator<Item = Option<Unit<f64>>> {
+    items.into_iter().map(move |item| item.convert_to(target))
+}
+pub fn render_all(items: &[Unit<f64>], unit: NativeUnit) -> Option<Vec<Unit<f64>>> {
+    items.iter().map(|item| item.convert_to(unit)).collect()
+}
 
 #[macro_export]
 macro_rules! unit {
     ($v:expr, $unit:expr) => {
-        self::Unit::new($v, $unit)
+        $crate::Unit::new($v, $unit)
     };
 }
 #[macro_export]
 macro_rules! meter {
     ($v:expr) => {
-        self::Unit::new($v, UnitKind::Native(NativeUnit::Meter))
+        $crate::Unit::new($v, $crate::UnitKind::Native($crate::NativeUnit::Meter))
     };
 }
 #[macro_export]
 macro_rules! liter {
     ($v:expr) => {
-        ::Unit::new($v, UnitKind::Native(NativeUnit::Liter))
+        $crate::Unit::new($v, $crate::UnitKind::Native($crate::NativeUnit::Liter))
     };
 }
 #[macro_export]
 macro_rules! gramm {
     ($v:expr) => {
-        ::Unit::new($v, UnitKind::Native(NativeUnit::Gramm))
+        $crate::Unit::new($v, $crate::UnitKind::Native($crate::NativeUnit::Gramm))
     };
 }
 #[macro_export]
 macro_rules! second {
     ($v:expr) => {
-        self::Unit::new($v, UnitKind::Native(NativeUnit::Second))
+        $crate::Unit::new($v, $crate::UnitKind::Native($crate::NativeUnit::Second))
     };
 }
 #[macro_export]
 macro_rules! minute {
     ($v:expr) => {
-        ::Unit::new($v, UnitKind::Native(NativeUnit::Minute))
+        $crate::Unit::new($v, $crate::UnitKind::Native($crate::NativeUnit::Minute))
     };
 }
 #[macro_export]
 macro_rules! hour {
     ($v:expr) => {
-        ::Unit::new($v, UnitKind::Native(NativeUnit::Hour))
+        $crate::Unit::new($v, $crate::UnitKind::Native($crate::NativeUnit::Hour))
     };
 }
 #[macro_export]
 macro_rules! day {
     ($v:expr) => {
-        ::Unit::new($v, UnitKind::Native(NativeUnit::Day))
+        $crate::Unit::new($v, $crate::UnitKind::Native($crate::NativeUnit::Day))
+    };
+}
+#[macro_export]
+macro_rules! week {
+    ($v:expr) => {
+        $crate::Unit::new($v, $crate::UnitKind::Native($crate::NativeUnit::Week))
     };
 }
 #[macro_export]
 macro_rules! year {
     ($v:expr) => {
-        ::Unit::new($v, UnitKind::Native(NativeUnit::Year))
+        $crate::Unit::new($v, $crate::UnitKind::Native($crate::NativeUnit::Year))
     };
 }
 #[macro_export]
 macro_rules! m_pro_s {
     ($v:expr) => {
-        unit!($v, unit_pro!(native!(Meter), native!(Second)))
+        $crate::Unit::new($v, $crate::UnitKind::Pro(
+            Box::new($crate::UnitKind::Native($crate::NativeUnit::Meter)),
+            Box::new($crate::UnitKind::Native($crate::NativeUnit::Second))
+        ))
     };
 }
 #[macro_export]
 macro_rules! area {
     ($v:expr) => {
-        unit!($v, unit_pow!(native!(Meter), 2))
+        $crate::Unit::new($v, $crate::UnitKind::Pow(Box::new($crate::UnitKind::Native($crate::NativeUnit::Meter)), 2))
     };
 }
 #[macro_export]
 macro_rules! volume {
     ($v:expr) => {
-        unit!($v, unit_pow!(native!(Meter), 3))
+        $crate::Unit::new($v, $crate::UnitKind::Pow(Box::new($crate::UnitKind::Native($crate::NativeUnit::Meter)), 3))
     };
 }
+#[macro_export]
+macro_rules! quantities {
+    ($($v:expr),* => $unit:ident) => {
+        vec![$($crate::Unit::new($v, $crate::UnitKind::Native($crate::NativeUnit::$unit))),*]
+    };
+    ($($q:expr),* $(,)?) => {
+        vec![$($q),*]
+    };
+}
+
+mod parse;
+pub use parse::ParseUnitError;
 
 #[cfg(test)]
 mod tests;
\ No newline at end of file
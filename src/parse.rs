@@ -0,0 +1,215 @@
+use super::*;
+use std::{error::Error, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseUnitError(String);
+impl Display for ParseUnitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+impl Error for ParseUnitError {}
+
+impl NativeUnit {
+    pub(crate) fn from_symbol(symbol: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|unit| unit.to_string() == symbol)
+    }
+}
+impl UnitKind {
+    // natives first so "min" and "m" aren't read as prefixed, prefixes only go on the base units
+    fn from_symbol(symbol: &str) -> Self {
+        NativeUnit::from_symbol(symbol).map(Self::Native)
+            .or_else(|| SiPrefix::ALL.into_iter().find_map(|prefix| {
+                let unit = NativeUnit::from_symbol(symbol.strip_prefix(&prefix.to_string())?)?;
+                (unit.dimension().base_unit() == unit).then_some(Self::Prefixed(prefix, unit))
+            }))
+            .unwrap_or_else(|| Self::Custom(symbol.to_string()))
+    }
+}
+
+// splits "1.5e3 m/s" into "1.5e3" and " m/s"
+pub(crate) fn split_number(s: &str) -> (&str, &str) {
+    let bytes = s.as_bytes();
+    let mut end = 0;
+    if matches!(bytes.first(), Some(b'+' | b'-')) {
+        end += 1;
+    }
+    let start = end;
+    while bytes.get(end).is_some_and(|b| b.is_ascii_digit() || *b == b'.') {
+        end += 1;
+    }
+    // an `e` only starts an exponent when digits follow, otherwise it belongs to the unit
+    if end > start && matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let mut exp = end + 1;
+        if matches!(bytes.get(exp), Some(b'+' | b'-')) {
+            exp += 1;
+        }
+        if bytes.get(exp).is_some_and(u8::is_ascii_digit) {
+            end = exp;
+            while bytes.get(end).is_some_and(u8::is_ascii_digit) {
+                end += 1;
+            }
+        }
+    }
+    s.split_at(end)
+}
+
+struct UnitParser<'a> {
+    src: &'a str,
+    pos: usize
+}
+impl<'a> UnitParser<'a> {
+    fn error(&self, message: &str) -> ParseUnitError {
+        ParseUnitError(format!("{message} at position {} in unit {:?}", self.pos, self.src))
+    }
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.pos += c.len_utf8();
+        }
+    }
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let start = self.pos;
+        while let Some(c) = self.peek().filter(|c| f(*c)) {
+            self.pos += c.len_utf8();
+        }
+        &self.src[start..self.pos]
+    }
+    // `*` and `/` share a precedence level and group to the left
+    fn expr(&mut self) -> Result<UnitKind, ParseUnitError> {
+        let mut unit = self.term()?;
+        loop {
+            if self.eat('*') {
+                unit = UnitKind::Per(Box::new(unit), Box::new(self.operand('*')?));
+            } else if self.eat('/') {
+                unit = UnitKind::Pro(Box::new(unit), Box::new(self.operand('/')?));
+            } else {
+                return Ok(unit)
+            }
+        }
+    }
+    // only a leading operand may be left out, as in "/s"
+    fn operand(&mut self, op: char) -> Result<UnitKind, ParseUnitError> {
+        self.skip_whitespace();
+        match self.peek() {
+            None | Some('*' | '/' | '^' | ')') => Err(self.error(&format!("expected a unit after '{op}'"))),
+            _ => self.term(),
+        }
+    }
+    fn term(&mut self) -> Result<UnitKind, ParseUnitError> {
+        let mut unit = self.atom()?;
+        while self.eat('^') {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(c) if c.is_ascii_digit() => {}
+                Some('-') => return Err(self.error("negative exponents are not supported, divide instead")),
+                _ => return Err(self.error("expected an exponent after '^'")),
+            }
+            let pow = self.take_while(|c| c.is_ascii_digit()).parse()
+                .map_err(|_| self.error("exponent is too large"))?;
+            unit = UnitKind::Pow(Box::new(unit), pow);
+        }
+        Ok(unit)
+    }
+    fn atom(&mut self) -> Result<UnitKind, ParseUnitError> {
+        if self.eat('(') {
+            let unit = self.expr()?;
+            if !self.eat(')') {
+                return Err(self.error("expected ')'"))
+            }
+            return Ok(unit)
+        }
+        self.skip_whitespace();
+        let symbol = self.take_while(|c| !c.is_whitespace() && !"*/^()".contains(c));
+        Ok(match symbol {
+            "" => UnitKind::None,
+            symbol => UnitKind::from_symbol(symbol),
+        })
+    }
+}
+pub(crate) fn parse_unit_kind(s: &str) -> Result<UnitKind, ParseUnitError> {
+    let mut parser = UnitParser { src: s, pos: 0 };
+    let unit = parser.expr()?;
+    parser.skip_whitespace();
+    match parser.peek() {
+        Some(c) => Err(parser.error(&format!("unexpected {c:?}"))),
+        None if !unit.exponents_fit() => Err(parser.error("exponent is too large")),
+        None => Ok(unit),
+    }
+}
+// parses what Display emits, except for None right of an operator and custom units
+// whose names contain whitespace or one of `*/^()`, or collide with a native or prefixed symbol
+impl FromStr for UnitKind {
+    type Err = ParseUnitError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_unit_kind(s)
+    }
+}
+
+impl Unit<f64> {
+    pub fn from_scientific_str(s: &str) -> Option<Self> {
+        s.parse().ok()
+    }
+}
+#[cfg(feature = "serde")]
+impl Unit<f64> {
+    // from {"value": 10.0, "unit": "m/s"}
+    pub fn try_from_json_value(json: &serde_json::Value) -> Option<Self> {
+        let value = json.get("value")?.as_f64()?;
+        let unit = parse_unit_kind(json.get("unit")?.as_str()?).ok()?;
+        Some(Self::new(value, unit))
+    }
+}
+
+fn parse_integer_quantity<T: FromStr>(s: &str) -> Result<Unit<T>, ParseUnitError> {
+    let s = s.trim();
+    let unsigned = s.strip_prefix(['+', '-']).unwrap_or(s);
+    let end = s.len() - unsigned.len() + unsigned.bytes().take_while(u8::is_ascii_digit).count();
+    let (number, unit) = s.split_at(end);
+    if unit.starts_with('.') {
+        return Err(ParseUnitError(format!("unexpected decimal point in integer quantity {s:?}")))
+    }
+    let value = number.parse().map_err(|_| ParseUnitError(format!("invalid integer {number:?}")))?;
+    Ok(Unit::new(value, parse_unit_kind(unit)?))
+}
+// "10 m/s", "-3.5m^2" or "1e3 g", a bare number is dimensionless
+fn parse_float_quantity<T: FromStr>(s: &str) -> Result<Unit<T>, ParseUnitError> {
+    let (number, unit) = split_number(s.trim());
+    let value = number.parse().map_err(|_| ParseUnitError(format!("invalid number {number:?} in quantity {s:?}")))?;
+    Ok(Unit::new(value, parse_unit_kind(unit)?))
+}
+impl FromStr for Unit<f64> {
+    type Err = ParseUnitError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_float_quantity(s)
+    }
+}
+impl FromStr for Unit<f32> {
+    type Err = ParseUnitError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_float_quantity(s)
+    }
+}
+impl FromStr for Unit<i64> {
+    type Err = ParseUnitError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_integer_quantity(s)
+    }
+}
+impl FromStr for Unit<i32> {
+    type Err = ParseUnitError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_integer_quantity(s)
+    }
+}
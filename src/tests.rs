@@ -29,4 +29,1267 @@ fn test_volume() {
 fn test_negative() {
     let width = meter!(20);
     assert_eq!(-width, meter!(-20));
-}
\ No newline at end of file
+    assert_eq!(-meter!(5.0), meter!(-5.0));
+    assert_eq!(-m_pro_s!(2_i64), m_pro_s!(-2));
+    assert_eq!(-&second!(1.5), second!(-1.5));
+}
+
+#[test]
+fn test_borrowed_ops() {
+    let a = meter!(20.);
+    let b = meter!(5.);
+    assert_eq!(&a + &b, meter!(25.));
+    assert_eq!(&a - &b, meter!(15.));
+    assert_eq!(&a * &b, area!(100.));
+    assert_eq!(&a / &b, unit!(4., UnitKind::None));
+    assert_eq!(a, meter!(20.));
+    assert_eq!(b, meter!(5.));
+}
+
+#[test]
+fn test_borrowed_negative() {
+    let width = meter!(20);
+    assert_eq!(-&width, meter!(-20));
+    assert_eq!(width, meter!(20));
+}
+
+#[test]
+fn test_common_unit() {
+    let items = vec![
+        unit!(90., native!(Minute)),
+        unit!(2., native!(Hour)),
+        second!(7200.),
+    ];
+    assert_eq!(common_unit(&items), Some(NativeUnit::Hour));
+    assert_eq!(render_all(&items, NativeUnit::Hour), Some(vec![
+        unit!(1.5, native!(Hour)),
+        unit!(2., native!(Hour)),
+        unit!(2., native!(Hour)),
+    ]));
+    assert_eq!(common_unit(&[meter!(500.), meter!(1500.)]), Some(NativeUnit::Meter));
+    assert_eq!(common_unit(&[meter!(500.), second!(2.)]), None);
+    assert_eq!(common_unit(&[]), None);
+}
+
+#[test]
+fn test_positive_unit() {
+    let length = PositiveUnit::try_from(meter!(3.)).unwrap();
+    assert_eq!(length.get(), &meter!(3.));
+    assert!(PositiveUnit::try_from(meter!(0.)).is_ok());
+    assert_eq!(PositiveUnit::try_from(meter!(-3.)), Err(meter!(-3.)));
+    assert_eq!(meter!(-1.).assert_positive(), Err(meter!(-1.)));
+}
+
+#[test]
+fn test_normalize_to_base() {
+    let mut items = vec![
+        unit!(2., native!(Hour)),
+        unit!(30., native!(Minute)),
+        second!(5.),
+        m_pro_s!(3.),
+    ];
+    normalize_to_base(&mut items);
+    assert_eq!(items, vec![second!(7200.), second!(1800.), second!(5.), m_pro_s!(3.)]);
+}
+
+#[test]
+fn test_rounded() {
+    let speed = meter!(10.) / second!(3.);
+    assert_eq!(Rounded(speed.clone(), 2).to_string(), "3.33m/s");
+    assert_eq!(Rounded(meter!(2.5), 0).to_string(), "2m");
+    assert_eq!(speed.display_rounded(1).to_string(), "3.3m/s");
+}
+
+#[test]
+fn test_try_into_native() {
+    assert_eq!(meter!(3).try_into_native(), Some((3, NativeUnit::Meter)));
+    assert_eq!(m_pro_s!(3).try_into_native(), None);
+    assert_eq!(unit!(3, UnitKind::Custom("ft".into())).try_into_native(), None);
+}
+
+#[test]
+fn test_percentage_change() {
+    assert_eq!(meter!(150.).percentage_change(&meter!(100.)), Some(50.));
+    assert_eq!(meter!(50.).percentage_change(&meter!(100.)), Some(-50.));
+    assert_eq!(meter!(50.).percentage_change(&meter!(0.)), None);
+    assert_eq!(meter!(50.).percentage_change(&second!(100.)), None);
+}
+
+#[test]
+fn test_midpoint() {
+    assert_eq!(meter!(0.).midpoint(&meter!(10.)), meter!(5.));
+    assert_eq!(meter!(f64::MAX).midpoint(&meter!(f64::MAX)), meter!(f64::MAX));
+}
+
+#[test]
+#[should_panic]
+fn test_midpoint_mismatch() {
+    meter!(0.).midpoint(&second!(10.));
+}
+
+#[test]
+fn test_dimension() {
+    assert_eq!(NativeUnit::Meter.dimension(), BaseDimension::Length);
+    assert_eq!(NativeUnit::Hour.dimension(), BaseDimension::Time);
+    assert_eq!(NativeUnit::Liter.dimension(), BaseDimension::Volume);
+    assert_eq!(BaseDimension::Time.base_unit(), NativeUnit::Second);
+    assert_eq!(meter!(1.).convert_to(NativeUnit::Second), None);
+    assert_eq!(unit!(2., native!(Hour)).convert_to(NativeUnit::Minute), Some(unit!(120., native!(Minute))));
+}
+
+#[test]
+fn test_is_compatible_with() {
+    assert!(unit!(1., native!(Hour)).is_compatible_with(&unit!(30., native!(Minute))));
+    assert!(m_pro_s!(1.).is_compatible_with(&m_pro_s!(2.)));
+    assert!(!meter!(1.).is_compatible_with(&second!(1.)));
+}
+
+#[test]
+fn test_round_half_even() {
+    assert_eq!(meter!(2.5).round_half_even(0), meter!(2.));
+    assert_eq!(meter!(3.5).round_half_even(0), meter!(4.));
+    assert_eq!(2.5_f64.round(), 3.);
+    assert_eq!(meter!(0.125).round_half_even(2), meter!(0.12));
+    assert_eq!(meter!(2.6).round_half_even(0), meter!(3.));
+}
+
+#[test]
+fn test_quantities() {
+    let lengths = quantities![1., 2., 3. => Meter];
+    assert_eq!(lengths, vec![meter!(1.), meter!(2.), meter!(3.)]);
+    assert_eq!(quantities![meter!(1.), meter!(2.), meter!(3.)], lengths);
+    assert_eq!(quantities![meter!(1.),], vec![meter!(1.)]);
+}
+
+#[test]
+fn test_from_scientific_str() {
+    assert_eq!(Unit::from_scientific_str("1.5e3 m"), Some(meter!(1500.)));
+    assert_eq!(Unit::from_scientific_str("2E-2 s"), Some(second!(0.02)));
+    assert_eq!(Unit::from_scientific_str("1.5e3 m/s"), Some(m_pro_s!(1500.)));
+    assert_eq!(Unit::from_scientific_str("-4e+1m^2"), Some(area!(-40.)));
+    assert_eq!(Unit::from_scientific_str("3eggs"), Some(unit!(3., UnitKind::Custom("eggs".into()))));
+    assert_eq!(Unit::from_scientific_str("e3 m"), None);
+    assert_eq!(Unit::from_scientific_str("3 m^"), None);
+}
+
+#[test]
+fn test_invert_value() {
+    let gain = unit!(4., UnitKind::Custom("x".into()));
+    assert_eq!(gain.invert_value(), Some(unit!(0.25, UnitKind::Custom("x".into()))));
+    assert_eq!(meter!(0.).invert_value(), None);
+}
+
+#[test]
+fn test_compare_report() {
+    let hour = unit!(1., native!(Hour));
+    assert_eq!(hour.compare_report(&unit!(60., native!(Minute))), "1h is equal to 60min");
+    assert_eq!(hour.compare_report(&unit!(90., native!(Minute))), "1h is less than 90min");
+    assert_eq!(hour.compare_report(&second!(60.)), "1h is greater than 60s");
+    assert_eq!(hour.compare_report(&meter!(1.)), "incomparable (different dimensions)");
+}
+
+#[test]
+fn test_split_at_unit_boundary() {
+    let labels = ["short", "medium", "long"];
+    let thresholds = [meter!(10.), meter!(100.)];
+    let bins: Vec<_> = [meter!(3.), meter!(10.), meter!(42.), meter!(512.)].iter()
+        .map(|distance| labels[distance.split_at_unit_boundary(&thresholds).unwrap()])
+        .collect();
+    assert_eq!(bins, ["short", "medium", "medium", "long"]);
+    assert_eq!(second!(3.).split_at_unit_boundary(&thresholds), None);
+}
+
+#[test]
+fn test_to_unit_system() {
+    let feet = meter!(3.048).to_unit_system(UnitSystem::Imperial).unwrap();
+    assert_eq!(feet.unit_ref(), &UnitKind::Custom("ft".into()));
+    assert!((feet.value_ref() - 10.).abs() < 1e-9);
+    let gallons = unit!(45.4609, native!(Liter)).to_unit_system(UnitSystem::Imperial).unwrap();
+    assert_eq!(gallons.unit_ref(), &UnitKind::Custom("imp_gal".into()));
+    assert!((gallons.value_ref() - 10.).abs() < 1e-9);
+    assert_eq!(unit!(2., native!(Hour)).to_unit_system(UnitSystem::SI), Some(second!(7200.)));
+    assert_eq!(m_pro_s!(2.).to_unit_system(UnitSystem::Imperial), None);
+}
+
+#[test]
+fn test_clamp_reporting() {
+    assert_eq!(meter!(5.).clamp_reporting(meter!(0.), meter!(10.)), (meter!(5.), false));
+    assert_eq!(meter!(15.).clamp_reporting(meter!(0.), meter!(10.)), (meter!(10.), true));
+    assert_eq!(meter!(-1.).clamp_reporting(meter!(0.), meter!(10.)), (meter!(0.), true));
+    let minutes = unit!(90., native!(Minute));
+    assert_eq!(
+        minutes.clamp_reporting(second!(0.), unit!(1., native!(Hour))),
+        (unit!(60., native!(Minute)), true)
+    );
+}
+
+#[test]
+fn test_geomean_rate() {
+    let rates = [unit!(0.5, UnitKind::None), unit!(2., UnitKind::None)];
+    assert_eq!(geomean_rate(&rates), Some(unit!(1., UnitKind::None)));
+    assert_eq!(geomean_rate(&[]), None);
+    assert_eq!(geomean_rate(&[meter!(2.)]), None);
+    assert_eq!(geomean_rate(&[unit!(-2., UnitKind::None)]), None);
+}
+
+#[test]
+fn test_reduce() {
+    let speed = unit_pro!(native!(Meter), native!(Second));
+    assert_eq!(unit_per!(speed.clone(), native!(Second)).reduce(), native!(Meter));
+    assert_eq!(unit_per!(native!(Second), speed.clone()).reduce(), native!(Meter));
+    let action = unit_per!(native!(Meter), native!(Second));
+    assert_eq!(unit_pro!(action.clone(), native!(Second)).reduce(), native!(Meter));
+    assert_eq!(unit_pro!(action, native!(Meter)).reduce(), native!(Second));
+    let nested = unit_pow!(unit_per!(speed.clone(), native!(Second)), 2);
+    assert_eq!(nested.reduce(), unit_pow!(native!(Meter), 2));
+    assert_eq!(unit_per!(speed.clone(), native!(Meter)).reduce(), unit_per!(speed, native!(Meter)));
+}
+
+#[test]
+fn test_to_base_with_factor() {
+    assert_eq!(unit!(2., native!(Hour)).to_base_with_factor(), Some((second!(7200.), 3600.)));
+    assert_eq!(meter!(2.).to_base_with_factor(), Some((meter!(2.), 1.)));
+    assert_eq!(m_pro_s!(2.).to_base_with_factor(), None);
+}
+
+#[test]
+fn test_duration_unit() {
+    let lap = unit!(Duration::from_millis(1500), native!(Second));
+    let total = lap.clone() + unit!(Duration::from_secs(2), native!(Second));
+    assert_eq!(total, unit!(Duration::from_millis(3500), native!(Second)));
+    assert_eq!(total.to_seconds_unit(), second!(3.5));
+    assert_eq!((total - lap).to_seconds_unit(), second!(2.));
+}
+
+#[test]
+fn test_as_ratio_unit() {
+    assert_eq!(unit_pro!(native!(Meter), native!(Meter)).reduce(), UnitKind::None);
+    assert_eq!(unit_pro!(unit_pow!(native!(Meter), 3), native!(Meter)).reduce(), unit_pow!(native!(Meter), 2));
+    assert_eq!(unit_pro!(area!(1.).unit(), native!(Meter)).reduce(), native!(Meter));
+    assert_eq!(unit_pro!(volume!(1.).unit(), area!(1.).unit()).reduce(), native!(Meter));
+    let ratio = unit!(0.5, unit_pro!(native!(Meter), native!(Meter)));
+    assert_eq!(ratio.as_ratio_unit(), Some(unit!(0.5, UnitKind::None)));
+    assert_eq!(m_pro_s!(0.5).as_ratio_unit(), None);
+    assert_eq!(
+        unit_pro!(unit_pow!(native!(Meter), 0), native!(Meter)).reduce(),
+        unit_pro!(UnitKind::None, native!(Meter))
+    );
+}
+
+#[test]
+fn test_fuel_economy() {
+    let consumption = unit!(8., unit_pro!(native!(Liter), UnitKind::Custom("100km".into())));
+    let mpg = consumption.to_mpg().unwrap();
+    assert_eq!(mpg.unit_ref().to_string(), "mi/gal");
+    assert!((mpg.value_ref() - 29.4018).abs() < 1e-4);
+    let back = mpg.to_l_per_100km().unwrap();
+    assert_eq!(back.unit_ref(), consumption.unit_ref());
+    assert!((back.value_ref() - 8.).abs() < 1e-9);
+    let per_meter = unit!(0.0001, unit_pro!(native!(Liter), native!(Meter)));
+    assert!((per_meter.to_l_per_100km().unwrap().value() - 10.).abs() < 1e-9);
+    assert_eq!(m_pro_s!(1.).to_mpg(), None);
+}
+
+#[test]
+fn test_clamp_nonneg_converting() {
+    let max = unit!(1., native!(Hour));
+    assert_eq!(unit!(90., native!(Minute)).clamp_nonneg_converting(max.clone()), unit!(60., native!(Minute)));
+    assert_eq!(unit!(-5., native!(Minute)).clamp_nonneg_converting(max.clone()), unit!(0., native!(Minute)));
+    assert_eq!(unit!(5., native!(Minute)).clamp_nonneg_converting(max), unit!(5., native!(Minute)));
+}
+
+#[test]
+fn test_significant_digits() {
+    assert_eq!(meter!(1200.).significant_digits(), 2);
+    assert_eq!(meter!(7.).significant_digits(), 1);
+    assert_eq!(meter!(2.75).significant_digits(), 3);
+    assert_eq!(meter!(100.5).significant_digits(), 4);
+    assert_eq!(meter!(-0.0012).significant_digits(), 2);
+    assert_eq!(meter!(0.).significant_digits(), 1);
+    assert_eq!(meter!(f64::NAN).significant_digits(), 0);
+}
+
+#[test]
+fn test_name_of_dimension() {
+    let acceleration = unit!(9.81, unit_pro!(native!(Meter), unit_pow!(native!(Second), 2)));
+    assert_eq!(acceleration.name_of_dimension(), Some("acceleration"));
+    let force = unit!(1., unit_pro!(unit_per!(native!(Gramm), native!(Meter)), unit_pow!(native!(Second), 2)));
+    assert_eq!(force.name_of_dimension(), Some("newton"));
+    assert_eq!(unit!(1., unit_pro!(native!(Liter), native!(Meter))).name_of_dimension(), Some("area"));
+    assert_eq!(m_pro_s!(1.).name_of_dimension(), Some("speed"));
+    assert_eq!(meter!(1.).name_of_dimension(), None);
+    assert_eq!(unit!(1., UnitKind::Custom("ft".into())).name_of_dimension(), None);
+}
+
+#[test]
+fn test_parse_integer_unit() {
+    assert_eq!("20m".parse::<Unit<i64>>(), Ok(meter!(20)));
+    assert_eq!("-3 m/s".parse::<Unit<i32>>(), Ok(m_pro_s!(-3)));
+    assert_eq!("7".parse::<Unit<i32>>(), Ok(unit!(7, UnitKind::None)));
+    assert!("20.5m".parse::<Unit<i64>>().is_err());
+    assert!("m".parse::<Unit<i64>>().is_err());
+    assert!("99999999999m".parse::<Unit<i32>>().is_err());
+}
+
+#[test]
+fn test_convert_to_custom() {
+    let furlongs = meter!(402.336).convert_to_custom("furlong", 1. / 201.168);
+    assert_eq!(furlongs, unit!(2., UnitKind::Custom("furlong".into())));
+    assert_eq!(furlongs.to_string(), "2furlong");
+}
+
+#[test]
+fn test_abs_diff() {
+    let hour = unit!(1., native!(Hour));
+    assert_eq!(hour.abs_diff(&unit!(30., native!(Minute))), Some(unit!(0.5, native!(Hour))));
+    assert_eq!(meter!(2.).abs_diff(&meter!(5.)), Some(meter!(3.)));
+    assert_eq!(hour.abs_diff(&meter!(5.)), None);
+}
+
+#[test]
+fn test_checked_convert_chain() {
+    let days = unit!(2., native!(Day));
+    assert_eq!(
+        days.checked_convert_chain(&[NativeUnit::Hour, NativeUnit::Minute]),
+        Some(unit!(2880., native!(Minute)))
+    );
+    assert_eq!(days.checked_convert_chain(&[NativeUnit::Hour, NativeUnit::Meter]), None);
+    assert_eq!(days.checked_convert_chain(&[]), Some(days));
+}
+
+#[test]
+fn test_default_precision() {
+    assert_eq!(default_precision(), None);
+    set_default_precision(Some(2));
+    assert_eq!(default_precision(), Some(2));
+    assert_eq!(meter!(1. / 3.).to_string(), "0.33m");
+    assert_eq!(meter!(3).to_string(), "3m");
+    assert_eq!(format!("{:.1}", meter!(1. / 3.)), "0.3m");
+    assert_eq!(meter!(1_f32 / 3.).to_string(), "0.33m");
+    let word = unit!("hello".to_string(), UnitKind::Custom("x".into()));
+    assert_eq!(word.to_string(), "hellox");
+    assert_eq!(word.display_without_unit(), "hello");
+    assert_eq!(unit!(&(1. / 3.), UnitKind::None).to_string(), "0.33");
+    set_default_precision(None);
+    assert_eq!(meter!(0.5).to_string(), "0.5m");
+}
+
+#[test]
+fn test_ppm() {
+    let ratio = Unit::from_ppm(500.);
+    assert_eq!(ratio, unit!(0.0005, UnitKind::None));
+    assert!((ratio.to_ppm().unwrap() - 500.).abs() < 1e-9);
+    assert_eq!(Ppm::try_from(unit!(0.25, UnitKind::None)).unwrap().to_string(), "250000ppm");
+    assert!(Ppm::try_from(meter!(0.25)).is_err());
+    assert_eq!(meter!(1.).to_ppm(), None);
+}
+
+#[test]
+fn test_weighted_mean() {
+    let measurements = [(meter!(10.), 1.), (meter!(20.), 2.), (meter!(40.), 1.)];
+    assert_eq!(weighted_mean(&measurements), Some(meter!(22.5)));
+    assert_eq!(weighted_mean(&[(meter!(10.), 1.), (second!(20.), 1.)]), None);
+    assert_eq!(weighted_mean(&[(meter!(10.), 0.)]), None);
+    assert_eq!(weighted_mean(&[]), None);
+}
+
+#[test]
+fn test_nearest_named_prefix() {
+    assert_eq!(meter!(5000.).nearest_named_prefix(), Some(SiPrefix::Kilo));
+    assert_eq!(meter!(0.003).nearest_named_prefix(), Some(SiPrefix::Milli));
+    assert_eq!(meter!(-2e7).nearest_named_prefix(), Some(SiPrefix::Mega));
+    assert_eq!(meter!(0.05).nearest_named_prefix(), Some(SiPrefix::Milli));
+    assert_eq!(meter!(12.).nearest_named_prefix(), None);
+    assert_eq!(meter!(0.).nearest_named_prefix(), None);
+    assert_eq!(SiPrefix::Micro.to_string(), "µ");
+}
+
+#[test]
+fn test_is_approx_integer() {
+    assert!(meter!(4.9999999).is_approx_integer(1e-6));
+    assert!(meter!(-3.0000001).is_approx_integer(1e-6));
+    assert!(!meter!(4.9999999).is_approx_integer(0.));
+    assert!(!meter!(4.5).is_approx_integer(1e-6));
+}
+
+#[test]
+fn test_combine() {
+    let half = unit!(30., native!(Minute));
+    let hour = unit!(1., native!(Hour));
+    assert_eq!(half.clone().combine(hour.clone(), UnitPreference::Larger), Some(unit!(1.5, native!(Hour))));
+    assert_eq!(half.clone().combine(hour.clone(), UnitPreference::Smaller), Some(unit!(90., native!(Minute))));
+    assert_eq!(half.clone().combine(hour.clone(), UnitPreference::Left), Some(unit!(90., native!(Minute))));
+    assert_eq!(half.clone().combine(hour, UnitPreference::Right), Some(unit!(1.5, native!(Hour))));
+    assert_eq!(half.combine(meter!(1.), UnitPreference::Larger), None);
+}
+
+#[test]
+fn test_sqrt() {
+    assert_eq!(area!(9.).sqrt(), Ok(meter!(3.)));
+    assert_eq!(area!(-4.).sqrt(), Err(UnitError::NegativeRoot));
+    assert_eq!(volume!(8.).sqrt(), Err(UnitError::NoExactRoot { unit: unit_pow!(native!(Meter), 3), degree: 2 }));
+    assert_eq!(unit!(16., unit_pow!(native!(Second), 4)).sqrt(), Ok(unit!(4., unit_pow!(native!(Second), 2))));
+    assert_eq!(unit!(4., UnitKind::None).sqrt(), Ok(unit!(2., UnitKind::None)));
+    assert!(meter!(4.).sqrt().is_err());
+    let compound = unit_per!(unit_pow!(native!(Meter), 2), unit_pow!(native!(Second), 2));
+    assert_eq!(unit!(4., compound.clone()).sqrt(), Ok(unit!(2., unit_per!(native!(Meter), native!(Second)))));
+    assert_eq!(unit!(4., compound.clone()).sqrt().ok(), unit!(4., compound).try_pow_rational(1, 2));
+    let rate = unit_pro!(unit_pow!(native!(Meter), 2), unit_pow!(native!(Second), 2));
+    assert_eq!(unit!(9., rate).sqrt(), Ok(m_pro_s!(3.)));
+}
+
+#[test]
+fn test_product() {
+    assert_eq!(product([meter!(2.), meter!(3.), meter!(4.)]), volume!(24.));
+    assert_eq!(product(vec![meter!(2.)]), meter!(2.));
+    assert_eq!(product([]), unit!(1., UnitKind::None));
+}
+
+#[test]
+fn test_checked_mul_capped() {
+    assert_eq!(area!(2.).checked_mul_capped(meter!(3.), 3), Some(volume!(6.)));
+    assert_eq!(volume!(2.).checked_mul_capped(meter!(3.), 3), None);
+    assert_eq!(meter!(2.).checked_mul_capped(second!(3.), 1), Some(unit!(6., unit_per!(native!(Meter), native!(Second)))));
+}
+
+#[test]
+fn test_to_value_in_base() {
+    assert_eq!(unit!(1., native!(Hour)).to_value_in_base(), 3600.);
+    assert_eq!(meter!(2.).to_value_in_base(), 2.);
+}
+
+#[test]
+#[should_panic]
+fn test_to_value_in_base_compound() {
+    m_pro_s!(1.).to_value_in_base();
+}
+
+#[test]
+fn test_scalar_rem() {
+    let degree = UnitKind::Custom("deg".into());
+    assert_eq!(Unit::new(370., degree.clone()) % 360., Unit::new(10., degree.clone()));
+    assert_eq!(Unit::new(-30_f32, degree.clone()) % 360_f32, Unit::new(-30_f32, degree));
+}
+
+#[test]
+fn test_convert_preserving_prefix() {
+    let km = meter!(5000.).convert_preserving_prefix(NativeUnit::Meter).unwrap();
+    assert_eq!(km, Unit::new(5., UnitKind::Prefixed(SiPrefix::Kilo, NativeUnit::Meter)));
+    assert_eq!(km.to_string(), "5km");
+    assert_eq!(unit!(2., native!(Hour)).convert_preserving_prefix(NativeUnit::Second).unwrap().to_string(), "7.2ks");
+    assert_eq!(second!(0.5).convert_preserving_prefix(NativeUnit::Second).unwrap().to_string(), "500ms");
+    assert_eq!(second!(7200.).convert_preserving_prefix(NativeUnit::Hour), Some(unit!(2., native!(Hour))));
+    assert_eq!(second!(1.).convert_preserving_prefix(NativeUnit::Meter), None);
+}
+
+#[test]
+fn test_dimension_string() {
+    assert_eq!(m_pro_s!(1.).dimension_string(), "L T^-1");
+    assert_eq!(area!(1.).dimension_string(), "L^2");
+    let acceleration = unit!(9.81, unit_pro!(native!(Meter), unit_pow!(native!(Second), 2)));
+    assert_eq!(acceleration.dimension_string(), "L T^-2");
+    assert_eq!(unit!(1., native!(Liter)).dimension_string(), "L^3");
+    assert_eq!(unit!(1., UnitKind::None).dimension_string(), "1");
+    assert_eq!(unit!(1., UnitKind::Custom("ft".into())).dimension_string(), "?");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_try_from_json_value() {
+    let json = serde_json::json!({"value": 10.0, "unit": "m/s"});
+    assert_eq!(Unit::try_from_json_value(&json), Some(m_pro_s!(10.)));
+    assert_eq!(Unit::try_from_json_value(&serde_json::json!({"value": 10.0})), None);
+    assert_eq!(Unit::try_from_json_value(&serde_json::json!({"value": "10", "unit": "m"})), None);
+}
+
+#[test]
+fn test_accumulate_over_time_series() {
+    let profile = [
+        (m_pro_s!(10.), second!(30.)),
+        (m_pro_s!(20.), unit!(1., native!(Minute))),
+        (m_pro_s!(5.), second!(10.)),
+    ];
+    assert_eq!(accumulate_over_time_series(&profile), Some(meter!(1550.)));
+    let km_per_h = unit_pro!(UnitKind::Prefixed(SiPrefix::Kilo, NativeUnit::Meter), native!(Hour));
+    let mixed = [
+        (unit!(36., km_per_h.clone()), unit!(1., native!(Minute))),
+        (m_pro_s!(10.), second!(30.)),
+    ];
+    assert_eq!(accumulate_over_time_series(&mixed), Some(meter!(900.)));
+    assert_eq!(accumulate_over_time_series(&[(unit!(3.6, km_per_h), unit!(1., native!(Hour)))]), Some(meter!(3600.)));
+    assert_eq!(accumulate_over_time_series(&[(m_pro_s!(10.), meter!(3.))]), None);
+    assert_eq!(accumulate_over_time_series(&[(meter!(10.), second!(3.))]), None);
+    assert_eq!(accumulate_over_time_series(&[]), None);
+}
+
+#[test]
+fn test_round_to_nearest_unit() {
+    assert_eq!(second!(5000.).round_to_nearest_unit(UnitSystem::SI), Some(unit!(1., native!(Hour))));
+    assert_eq!(meter!(5.4).round_to_nearest_unit(UnitSystem::SI), Some(meter!(5.)));
+    let mile = UnitKind::Custom("mi".into());
+    assert_eq!(meter!(2000.).round_to_nearest_unit(UnitSystem::Imperial), Some(unit!(1., mile)));
+    let feet = UnitKind::Custom("ft".into());
+    assert_eq!(meter!(1234.).round_to_nearest_unit(UnitSystem::USCustomary), Some(unit!(4049., feet)));
+    assert_eq!(m_pro_s!(2.).round_to_nearest_unit(UnitSystem::SI), None);
+}
+
+#[test]
+fn test_expect_dimension() {
+    assert_eq!(meter!(3.).expect_dimension(BaseDimension::Length), Ok(meter!(3.)));
+    let err = second!(3.).expect_dimension(BaseDimension::Length).unwrap_err();
+    assert_eq!(err, WrongDimension { expected: BaseDimension::Length, found: Some(BaseDimension::Time) });
+    assert_eq!(err.to_string(), "expected a Length quantity, found Time");
+    assert_eq!(m_pro_s!(3.).expect_dimension(BaseDimension::Length).unwrap_err().found, None);
+}
+
+#[test]
+fn test_display_without_unit() {
+    let speed = m_pro_s!(2.5);
+    let unit = speed.unit_ref().to_string();
+    assert_eq!(speed.display_without_unit(), speed.to_string().strip_suffix(&unit).unwrap());
+    set_default_precision(Some(2));
+    assert_eq!(speed.display_without_unit(), "2.50");
+    set_default_precision(None);
+}
+
+#[test]
+fn test_convert_all() {
+    let times = [unit!(2., native!(Minute)), unit!(1., native!(Hour)), meter!(3.)];
+    let mut seconds = convert_all(times, NativeUnit::Second);
+    assert_eq!(seconds.next(), Some(Some(second!(120.))));
+    assert_eq!(seconds.next(), Some(Some(second!(3600.))));
+    assert_eq!(seconds.next(), Some(None));
+    assert_eq!(seconds.next(), None);
+}
+
+#[test]
+fn test_unit_kind_round_trip() {
+    let mut kinds: Vec<UnitKind> = NativeUnit::ALL.into_iter().map(UnitKind::Native).collect();
+    kinds.extend([
+        UnitKind::None,
+        UnitKind::Custom("ft".into()),
+        UnitKind::Prefixed(SiPrefix::Micro, NativeUnit::Second),
+        unit_pro!(native!(Meter), native!(Second)),
+        unit_per!(native!(Meter), native!(Second)),
+        unit_pow!(native!(Meter), 2),
+        unit_pro!(native!(Meter), unit_pow!(native!(Second), 2)),
+        unit_pow!(unit_pro!(native!(Meter), native!(Second)), 2),
+        unit_pow!(unit_pow!(native!(Meter), 2), 3),
+        unit_pro!(native!(Meter), unit_per!(native!(Second), native!(Gramm))),
+        unit_per!(native!(Meter), unit_pro!(native!(Second), native!(Gramm))),
+        unit_pro!(unit_per!(native!(Meter), native!(Second)), native!(Gramm)),
+        unit_pro!(UnitKind::None, native!(Second)),
+    ]);
+    for kind in kinds {
+        assert_eq!(kind.to_string().parse::<UnitKind>(), Ok(kind));
+    }
+}
+
+#[test]
+fn test_unit_kind_from_str() {
+    assert_eq!("m/s^2".parse(), Ok(unit_pro!(native!(Meter), unit_pow!(native!(Second), 2))));
+    assert_eq!("m * s".parse(), Ok(unit_per!(native!(Meter), native!(Second))));
+    assert_eq!("".parse(), Ok(UnitKind::None));
+    assert_eq!("furlong".parse(), Ok(UnitKind::Custom("furlong".into())));
+    assert_eq!(
+        "m^".parse::<UnitKind>().unwrap_err().to_string(),
+        "expected an exponent after '^' at position 2 in unit \"m^\""
+    );
+    assert_eq!(
+        "m^-1".parse::<UnitKind>().unwrap_err().to_string(),
+        "negative exponents are not supported, divide instead at position 2 in unit \"m^-1\""
+    );
+    assert!("(m/s".parse::<UnitKind>().is_err());
+    assert!("m)".parse::<UnitKind>().is_err());
+}
+
+#[test]
+fn test_is_zero() {
+    assert!(meter!(0.).is_zero());
+    assert!(meter!(-0.).is_zero());
+    assert!(!meter!(0.5).is_zero());
+    assert!(unit!(0_i64, native!(Meter)).is_zero());
+    assert!(!unit!(3_i32, native!(Meter)).is_zero());
+}
+
+#[test]
+fn test_checked_add() {
+    assert_eq!(meter!(1.).checked_add(meter!(2.)), Ok(meter!(3.)));
+    assert_eq!(
+        meter!(1.).checked_add(second!(1.)),
+        Err(UnitError::Mismatch { left: native!(Meter), right: native!(Second) })
+    );
+    assert_eq!(
+        meter!(1.).checked_add(second!(1.)).unwrap_err().to_string(),
+        "mismatched units m and s"
+    );
+}
+
+#[test]
+fn test_checked_sub() {
+    assert_eq!(second!(5.).checked_sub(second!(2.)), Ok(second!(3.)));
+    assert!(meter!(5.).checked_sub(m_pro_s!(2.)).is_err());
+}
+
+#[test]
+#[should_panic(expected = "cannot add: mismatched units m and s")]
+fn test_add_mismatch_panics() {
+    let _ = meter!(1.) + second!(1.);
+}
+
+#[test]
+fn test_scale_unit_value_separately() {
+    assert_eq!(meter!(5000.).scale_unit_value_separately(), Some((5., 1000., NativeUnit::Meter)));
+    assert_eq!(unit!(2., native!(Hour)).scale_unit_value_separately(), Some((7.2, 1000., NativeUnit::Second)));
+    assert_eq!(meter!(12.).scale_unit_value_separately(), Some((12., 1., NativeUnit::Meter)));
+    assert_eq!(m_pro_s!(5000.).scale_unit_value_separately(), None);
+}
+
+#[test]
+fn test_from_base_quantity() {
+    let km = UnitKind::Prefixed(SiPrefix::Kilo, NativeUnit::Meter);
+    assert_eq!(Unit::from_base_quantity(1000., BaseDimension::Length, km.clone()), Ok(unit!(1., km)));
+    assert_eq!(Unit::from_base_quantity(1000., BaseDimension::Length, native!(Meter)), Ok(meter!(1000.)));
+    assert_eq!(Unit::from_base_quantity(7200., BaseDimension::Time, native!(Hour)), Ok(unit!(2., native!(Hour))));
+    assert_eq!(
+        Unit::from_base_quantity(1000., BaseDimension::Length, native!(Second)),
+        Err(WrongDimension { expected: BaseDimension::Length, found: Some(BaseDimension::Time) })
+    );
+    assert_eq!(
+        Unit::from_base_quantity(1000., BaseDimension::Length, m_pro_s!(1.).unit()),
+        Err(WrongDimension { expected: BaseDimension::Length, found: None })
+    );
+}
+
+#[test]
+fn test_unit_kind_as_map_key() {
+    let mut totals = std::collections::HashMap::new();
+    for quantity in [area!(2.), meter!(1.), area!(3.)] {
+        *totals.entry(quantity.unit_ref().clone()).or_insert(0.) += *quantity.value_ref();
+    }
+    assert_eq!(totals.len(), 2);
+    assert_eq!(totals[&unit_pow!(native!(Meter), 2)], 5.);
+}
+
+#[test]
+fn test_unit_hash() {
+    let set: std::collections::HashSet<_> = [meter!(1_i64), meter!(1_i64), second!(1_i64)].into_iter().collect();
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn test_try_pow_rational() {
+    assert_eq!(area!(16.).try_pow_rational(1, 2), Some(meter!(4.)));
+    let area = volume!(8.).try_pow_rational(2, 3).unwrap();
+    assert_eq!(area.unit_ref(), &unit_pow!(native!(Meter), 2));
+    assert!((area.value() - 4.).abs() < 1e-12);
+    assert_eq!(meter!(2.).try_pow_rational(-1, 1), Some(unit!(0.5, unit_pro!(UnitKind::None, native!(Meter)))));
+    assert_eq!(
+        unit!(4., unit_pro!(unit_pow!(native!(Meter), 2), unit_pow!(native!(Second), 2))).try_pow_rational(1, 2),
+        Some(m_pro_s!(2.))
+    );
+}
+
+#[test]
+fn test_try_pow_rational_fractional_exponent() {
+    assert_eq!(meter!(4.).try_pow_rational(1, 2), None);
+    assert_eq!(volume!(8.).try_pow_rational(1, 2), None);
+    assert_eq!(area!(4.).try_pow_rational(1, 0), None);
+}
+
+#[test]
+fn test_mul_pow_units() {
+    assert_eq!(area!(4.) * area!(4.), unit!(16., unit_pow!(native!(Meter), 4)));
+    assert_eq!(area!(2.) * volume!(3.), unit!(6., unit_pow!(native!(Meter), 5)));
+    assert_eq!(meter!(2.) * area!(3.), volume!(6.));
+    assert_eq!(area!(3.) * meter!(2.), volume!(6.));
+}
+
+#[test]
+fn test_unit_display() {
+    let acceleration = unit!(9.80665, unit_pro!(native!(Meter), unit_pow!(native!(Second), 2)));
+    assert_eq!(acceleration.display().to_string(), "9.80665m/s^2");
+    assert_eq!(acceleration.display().precision(2).unicode(true).to_string(), "9.81m/s²");
+    assert_eq!(acceleration.display().precision(1).with_space(true).unicode(true).to_string(), "9.8 m/s²");
+    let quantity = unit!(3.456, unit_per!(native!(Meter), unit_pow!(native!(Second), 12)));
+    assert_eq!(quantity.display().precision(1).unicode(true).to_string(), "3.5m·s¹²");
+}
+
+#[test]
+fn test_unit_display_system() {
+    assert_eq!(meter!(3.048).display().system(UnitSystem::Imperial).precision(0).to_string(), "10ft");
+    assert_eq!(m_pro_s!(2.).display().system(UnitSystem::Imperial).with_space(true).to_string(), "2 m/s");
+}
+
+#[test]
+fn test_div_pow_units() {
+    assert_eq!(volume!(6.) / meter!(2.), area!(3.));
+    assert_eq!(volume!(6.) / area!(2.), meter!(3.));
+    assert_eq!(area!(6.) / area!(2.), unit!(3., UnitKind::None));
+    assert_eq!(area!(6.) / meter!(2.), meter!(3.));
+}
+
+#[test]
+fn test_div_by_larger_pow() {
+    let per_square_meter = unit_pro!(UnitKind::None, unit_pow!(native!(Meter), 2));
+    assert_eq!(area!(6.) / unit!(2., unit_pow!(native!(Meter), 4)), unit!(3., per_square_meter.clone()));
+    assert_eq!(meter!(6.) / volume!(2.), unit!(3., per_square_meter));
+    assert_eq!(meter!(6.) / area!(2.), unit!(3., unit_pro!(UnitKind::None, native!(Meter))));
+}
+
+#[test]
+fn test_div_zero_pow() {
+    let dimensionless = unit!(1., "m^0".parse().unwrap());
+    assert_eq!(dimensionless / meter!(1.), unit!(1., unit_pro!(UnitKind::None, native!(Meter))));
+}
+
+#[test]
+fn test_canonicalize() {
+    assert_eq!(UnitKind::Pow(Box::new(native!(Meter)), 1).canonicalize(), native!(Meter));
+    assert_eq!(UnitKind::Pow(Box::new(native!(Meter)), 0).canonicalize(), UnitKind::None);
+    assert_eq!(unit_pow!(unit_pow!(native!(Second), 2), 3).canonicalize(), unit_pow!(native!(Second), 6));
+    assert_eq!(
+        unit_pro!(unit_pow!(native!(Meter), 1), unit_pow!(unit_pow!(native!(Second), 1), 2)).canonicalize(),
+        unit_pro!(native!(Meter), unit_pow!(native!(Second), 2))
+    );
+}
+
+#[test]
+fn test_unit_canonicalize() {
+    assert_eq!(unit!(2., unit_pow!(native!(Meter), 1)).canonicalize(), meter!(2.));
+}
+
+#[test]
+fn test_per_commutes() {
+    assert_eq!(unit_per!(native!(Meter), native!(Second)), unit_per!(native!(Second), native!(Meter)));
+    assert_eq!(meter!(2.) * second!(3.), second!(3.) * meter!(2.));
+    assert_ne!(unit_pro!(native!(Meter), native!(Second)), unit_pro!(native!(Second), native!(Meter)));
+}
+
+#[test]
+fn test_per_hash_commutes() {
+    let mut totals = std::collections::HashMap::new();
+    *totals.entry(unit_per!(native!(Meter), native!(Second))).or_insert(0.) += 1.;
+    *totals.entry(unit_per!(native!(Second), native!(Meter))).or_insert(0.) += 2.;
+    assert_eq!(totals.len(), 1);
+    assert_eq!(totals[&unit_per!(native!(Meter), native!(Second))], 3.);
+}
+
+#[test]
+fn test_partial_cmp() {
+    assert_eq!(meter!(1.0).partial_cmp(&second!(1.0)), None);
+    assert_eq!(meter!(1.0).partial_cmp(&meter!(1.0)), Some(Ordering::Equal));
+    assert_eq!(meter!(f64::NAN).partial_cmp(&meter!(1.0)), None);
+    assert!(meter!(1.0) <= meter!(1.0));
+    assert!(meter!(1.0) > meter!(0.5));
+}
+
+#[test]
+fn test_sort_same_unit() {
+    let mut lengths = vec![meter!(3.), meter!(1.), meter!(2.), meter!(1.)];
+    lengths.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(lengths, vec![meter!(1.), meter!(1.), meter!(2.), meter!(3.)]);
+}
+
+#[test]
+fn test_add_assign() {
+    let mut total = meter!(0.);
+    for length in [1., 2.5, 3.] {
+        total += meter!(length);
+    }
+    assert_eq!(total, meter!(6.5));
+    total -= meter!(0.5);
+    assert_eq!(total, meter!(6.));
+}
+
+#[test]
+fn test_mul_div_assign() {
+    let mut quantity = meter!(2.);
+    quantity *= meter!(3.);
+    assert_eq!(quantity, area!(6.));
+    quantity /= second!(2.);
+    assert_eq!(quantity, unit!(3., unit_pro!(unit_pow!(native!(Meter), 2), native!(Second))));
+}
+
+#[test]
+#[should_panic(expected = "cannot add: mismatched units m and s")]
+fn test_add_assign_mismatch_panics() {
+    let mut total = meter!(1.);
+    total += second!(1.);
+}
+
+#[test]
+fn test_sum() {
+    let total: Unit<f64> = [second!(1.5), second!(2.), second!(3.)].into_iter().sum();
+    assert_eq!(total, second!(6.5));
+    let empty: Unit<f64> = std::iter::empty().sum();
+    assert_eq!(empty, unit!(0., UnitKind::None));
+}
+
+#[test]
+fn test_product_trait() {
+    let volume: Unit<f64> = [meter!(2.), meter!(3.), meter!(4.)].into_iter().product();
+    assert_eq!(volume, volume!(24.));
+    let empty: Unit<i64> = std::iter::empty().product();
+    assert_eq!(empty, unit!(1, UnitKind::None));
+}
+
+#[test]
+fn test_convert() {
+    assert_eq!(hour!(2.0).convert(NativeUnit::Second), Ok(second!(7200.0)));
+    assert_eq!(unit!(1., native!(Year)).convert(NativeUnit::Day), Ok(unit!(365., native!(Day))));
+    assert_eq!(week!(1.).convert(NativeUnit::Week), Ok(week!(1.)));
+}
+
+#[test]
+fn test_convert_incompatible() {
+    assert_eq!(
+        meter!(1.).convert(NativeUnit::Second),
+        Err(UnitError::Incompatible { from: native!(Meter), to: native!(Second) })
+    );
+    assert_eq!(m_pro_s!(1.).convert(NativeUnit::Meter).unwrap_err().to_string(), "cannot convert m/s into m");
+}
+
+#[test]
+fn test_prefixed_display() {
+    assert_eq!(UnitKind::Prefixed(SiPrefix::Kilo, NativeUnit::Meter).to_string(), "km");
+    assert_eq!(UnitKind::Prefixed(SiPrefix::Centi, NativeUnit::Meter).to_string(), "cm");
+    assert_eq!(UnitKind::Prefixed(SiPrefix::Milli, NativeUnit::Gramm).to_string(), "mg");
+    assert_eq!("km/h".parse(), Ok(unit_pro!(UnitKind::Prefixed(SiPrefix::Kilo, NativeUnit::Meter), native!(Hour))));
+    assert_eq!("min".parse(), Ok(native!(Minute)));
+    assert_eq!("kh".parse(), Ok(UnitKind::Custom("kh".into())));
+}
+
+#[test]
+fn test_prefixed_to_base() {
+    let distance = Unit::new(3.0, UnitKind::Prefixed(SiPrefix::Kilo, NativeUnit::Meter));
+    assert_eq!(distance.clone().to_base(), meter!(3000.0));
+    assert_eq!(meter!(3000.0).convert(NativeUnit::Meter), Ok(meter!(3000.0)));
+    assert_eq!(distance.convert(NativeUnit::Meter), Ok(meter!(3000.0)));
+    assert_eq!(
+        Unit::new(250.0, UnitKind::Prefixed(SiPrefix::Milli, NativeUnit::Second)).convert(NativeUnit::Second),
+        Ok(second!(0.25))
+    );
+}
+
+#[test]
+fn test_prefixed_helpers() {
+    let km = |value: f64| Unit::new(value, UnitKind::Prefixed(SiPrefix::Kilo, NativeUnit::Meter));
+    assert!(km(1.).is_compatible_with(&meter!(1.)));
+    assert_eq!(km(2.).to_base_with_factor(), Some((meter!(2000.), 1000.)));
+    assert_eq!(km(2.).to_value_in_base(), 2000.);
+    assert_eq!(km(5.).scale_unit_value_separately(), Some((5., 1000., NativeUnit::Meter)));
+    assert_eq!(km(3.).expect_dimension(BaseDimension::Length), Ok(km(3.)));
+    assert_eq!(km(2.).to_unit_system(UnitSystem::SI), Some(meter!(2000.)));
+    assert_eq!(km(1.).checked_convert_chain(&[NativeUnit::Meter]), Some(meter!(1000.)));
+    let mut items = vec![km(2.), meter!(5.)];
+    normalize_to_base(&mut items);
+    assert_eq!(items, vec![meter!(2000.), meter!(5.)]);
+    assert_eq!(common_unit(&[meter!(500.), km(2.), meter!(1500.)]), Some(NativeUnit::Meter));
+    assert_eq!(meter!(500.).combine(km(1.), UnitPreference::Larger), Some(km(1.5)));
+    assert_eq!(meter!(500.).combine(km(1.), UnitPreference::Smaller), Some(meter!(1500.)));
+    assert_eq!(meter!(1234.).round_to_nearest_unit(UnitSystem::SI), Some(km(1.)));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_round_trip() {
+    let speed = m_pro_s!(10.0);
+    let json = serde_json::to_string(&speed).unwrap();
+    assert_eq!(json, r#"{"value":10.0,"unit":{"Pro":[{"Native":"Meter"},{"Native":"Second"}]}}"#);
+    assert_eq!(serde_json::from_str::<Unit<f64>>(&json).unwrap(), speed);
+    let kind = unit_pow!(UnitKind::Prefixed(SiPrefix::Kilo, NativeUnit::Meter), 2);
+    assert_eq!(serde_json::from_str::<UnitKind>(&serde_json::to_string(&kind).unwrap()).unwrap(), kind);
+}
+
+#[test]
+fn test_unit_kind_dimension() {
+    let speed = unit_pro!(native!(Meter), native!(Second));
+    assert_eq!(speed.dimension(), Some(BTreeMap::from([(NativeUnit::Meter, 1), (NativeUnit::Second, -1)])));
+    assert_eq!(unit_pro!(native!(Liter), unit_pow!(native!(Hour), 2)).dimension(),
+        Some(BTreeMap::from([(NativeUnit::Meter, 3), (NativeUnit::Second, -2)])));
+    assert_eq!(unit_pro!(native!(Meter), native!(Meter)).dimension(), Some(BTreeMap::new()));
+}
+
+#[test]
+fn test_exponent_overflow() {
+    assert!("m^4294967297".parse::<UnitKind>().is_err());
+    assert!("m^100000^100000".parse::<UnitKind>().is_err());
+    assert!("(m^100000)^100000".parse::<UnitKind>().is_err());
+    assert_eq!("m^2147483647".parse::<UnitKind>().unwrap().dimension(), Some(BTreeMap::from([(NativeUnit::Meter, i32::MAX)])));
+    let huge = unit_pow!(native!(Meter), 4294967297);
+    assert_eq!(huge.dimension(), None);
+    assert_eq!(unit_per!(unit_pow!(native!(Meter), 2147483647), native!(Meter)).dimension(), None);
+    assert!(!huge.same_dimension(&huge));
+    assert_eq!(huge.clone().simplify(), huge);
+    assert_eq!(unit!(1., huge).dimension_string(), "?");
+}
+
+#[test]
+fn test_same_dimension() {
+    let ms = unit_per!(native!(Meter), native!(Second));
+    let sm = unit_per!(native!(Second), native!(Meter));
+    assert_eq!(ms.dimension(), sm.dimension());
+    assert!(ms.same_dimension(&sm));
+    assert!(native!(Liter).same_dimension(&unit_pow!(native!(Meter), 3)));
+    assert!(!native!(Meter).same_dimension(&UnitKind::Custom("ft".into())));
+    assert!(UnitKind::Custom("ft".into()).same_dimension(&UnitKind::Custom("ft".into())));
+}
+
+#[test]
+fn test_cbrt() {
+    assert_eq!(volume!(27.).cbrt(), Ok(meter!(3.)));
+    assert_eq!(unit!(-8., unit_pow!(native!(Second), 6)).cbrt(), Ok(unit!(-2., unit_pow!(native!(Second), 2))));
+    assert_eq!(area!(8.).cbrt().unwrap_err().to_string(), "m^2 has no root of degree 3");
+}
+
+#[test]
+fn test_recip() {
+    let frequency = second!(2.0_f64).recip();
+    assert_eq!(frequency.value_ref(), &0.5);
+    assert_eq!(frequency.unit_ref().dimension(), Some(BTreeMap::from([(NativeUnit::Second, -1)])));
+    assert_eq!(frequency.recip(), second!(2.0));
+    assert_eq!(m_pro_s!(4.0_f64).recip(), unit!(0.25, unit_pro!(native!(Second), native!(Meter))));
+    assert_eq!(area!(2.0_f32).recip().unit_ref().dimension(), Some(BTreeMap::from([(NativeUnit::Meter, -2)])));
+    assert_eq!(unit!(4.0_f64, UnitKind::None).recip(), unit!(0.25, UnitKind::None));
+}
+
+#[test]
+fn test_rounding() {
+    assert_eq!(meter!(3.7_f64).floor(), meter!(3.0));
+    assert_eq!(meter!(3.2_f64).ceil(), meter!(4.0));
+    assert_eq!(m_pro_s!(2.5_f64).round(), m_pro_s!(3.0));
+    assert_eq!(second!(-2.7_f32).trunc(), second!(-2.0));
+    assert_eq!((meter!(7.0_f64) / second!(2.0)).round(), m_pro_s!(4.0));
+}
+
+#[test]
+fn test_abs() {
+    assert_eq!(meter!(-3.5_f64).abs(), meter!(3.5));
+    assert_eq!(second!(-1.5_f32).abs(), second!(1.5));
+    assert_eq!(meter!(-4_i64).abs(), meter!(4));
+    assert_eq!(meter!(-4_i32).abs(), meter!(4));
+}
+
+#[test]
+fn test_map() {
+    let length: Unit<f64> = meter!(3_i32).map(|v| v as f64);
+    assert_eq!(length, meter!(3.0));
+    let scale = 2.5;
+    assert_eq!(m_pro_s!(4.0).map(|v| v * scale), m_pro_s!(10.0));
+    assert_eq!(area!(2_i64).map(|v| v.to_string()).unit(), unit_pow!(native!(Meter), 2));
+}
+
+#[test]
+fn test_scalar_mul_div() {
+    assert_eq!(meter!(2.0) * 3.0, meter!(6.0));
+    assert_eq!(meter!(2.0) * 3_i32, meter!(6.0));
+    assert_eq!(meter!(2.0) * 3_i64, meter!(6.0));
+    assert_eq!(meter!(2.0) * 3_u32, meter!(6.0));
+    assert_eq!(meter!(2.0) * 3_u64, meter!(6.0));
+    assert_eq!(meter!(2.0) * 3_usize, meter!(6.0));
+    assert_eq!(meter!(6.0) / 3_i32, meter!(2.0));
+    assert_eq!(meter!(6.0) / 3_i64, meter!(2.0));
+    assert_eq!(meter!(6.0) / 3_u32, meter!(2.0));
+    assert_eq!(meter!(6.0) / 3_u64, meter!(2.0));
+    assert_eq!(meter!(6.0) / 3_usize, meter!(2.0));
+    assert_eq!(meter!(6.0) / 4.0, meter!(1.5));
+    assert_eq!(meter!(2.0_f32) * 0.5_f32, meter!(1.0_f32));
+    assert_eq!(meter!(2_i64) * 3_usize, meter!(6));
+    assert_eq!(meter!(2.0_f32) * 3_u8, meter!(6.0));
+    assert_eq!(meter!(2_i64) * 3_i64, meter!(6));
+    assert_eq!(meter!(2_i64) * 3_u32, meter!(6));
+    assert_eq!(meter!(2_i32) * 3_i32, meter!(6));
+    assert_eq!(meter!(2_u64) * 3_u64, meter!(6));
+    assert_eq!(meter!(2_usize) * 3_usize, meter!(6));
+    assert_eq!(meter!(2_isize) * 3_isize, meter!(6));
+    assert_eq!(m_pro_s!(6.0) / 4_u32, m_pro_s!(1.5));
+    assert_eq!(m_pro_s!(6_i32) / 3_i32, m_pro_s!(2));
+    assert_eq!(m_pro_s!(6_u32) / 3_u16, m_pro_s!(2));
+    assert_eq!(second!(7_u64) % 4_u64, second!(3));
+}
+
+#[test]
+fn test_scalar_wider_than_value() {
+    assert_eq!(meter!(0_u8) * 300_u16, meter!(0_u8));
+    assert_eq!(meter!(200_u8) / 300_u16, meter!(0_u8));
+    assert_eq!(meter!(200_u8) % 300_u16, meter!(200_u8));
+    assert_eq!(meter!(-128_i8) / 256_i16, meter!(0_i8));
+    assert_eq!(meter!(-100_i8) % 300_u64, meter!(-100_i8));
+}
+
+#[test]
+#[should_panic]
+fn test_scalar_overflow() {
+    let _ = meter!(2_u8) * 300_u32;
+}
+
+#[test]
+fn test_scalar_mul_vector() {
+    #[derive(Debug, PartialEq)]
+    struct Vector(f64, f64);
+    impl Mul<f64> for Vector {
+        type Output = Self;
+        fn mul(self, rhs: f64) -> Self::Output {
+            Self(self.0 * rhs, self.1 * rhs)
+        }
+    }
+    assert_eq!(Unit::new(Vector(1., 2.), native!(Meter)) * 2., Unit::new(Vector(2., 4.), native!(Meter)));
+}
+
+#[test]
+fn test_scalar_mul_newtype() {
+    #[derive(Debug, PartialEq)]
+    struct Cents(isize);
+    impl Mul<isize> for Cents {
+        type Output = Self;
+        fn mul(self, rhs: isize) -> Self::Output {
+            Self(self.0 * rhs)
+        }
+    }
+    impl Div<isize> for Cents {
+        type Output = Self;
+        fn div(self, rhs: isize) -> Self::Output {
+            Self(self.0 / rhs)
+        }
+    }
+    let price = Unit::new(Cents(250), UnitKind::Custom("ct".into()));
+    assert_eq!(price * 4_isize / 2_isize, Unit::new(Cents(500), UnitKind::Custom("ct".into())));
+}
+
+#[test]
+fn test_dimensionless_operand() {
+    let two = unit!(2., UnitKind::None);
+    assert_eq!(two.clone() * meter!(3.), meter!(6.));
+    assert_eq!((two.clone() * meter!(3.)).unit(), native!(Meter));
+    assert_eq!(meter!(3.) * two.clone(), meter!(6.));
+    assert_eq!(meter!(6.) / two.clone(), meter!(3.));
+    assert_eq!(two / meter!(4.), unit!(0.5, unit_pro!(UnitKind::None, native!(Meter))));
+    let mut length = meter!(3.);
+    length *= unit!(2., UnitKind::None);
+    assert_eq!(length, meter!(6.));
+}
+
+#[test]
+fn test_zero_one() {
+    let zero = Unit::<f64>::zero(native!(Meter));
+    assert_eq!(zero.value_ref(), &0.0);
+    assert_eq!(zero, meter!(0.0));
+    assert_eq!(Unit::<i64>::one(native!(Second)), second!(1));
+    assert_eq!(Unit::one(UnitKind::None) * meter!(3.0), meter!(3.0));
+    assert_eq!(meter!(3.0) / Unit::one(UnitKind::None), meter!(3.0));
+}
+
+#[cfg(feature = "num-traits")]
+#[test]
+fn test_num_traits_identities() {
+    use num_traits::{One, Zero};
+    let zero = <Unit<f64> as Zero>::zero();
+    assert_eq!(zero.unit_ref(), &UnitKind::None);
+    assert!(Zero::is_zero(&zero));
+    assert!(!Zero::is_zero(&meter!(0.0)));
+    assert_eq!(<Unit<f64> as One>::one() * m_pro_s!(2.0), m_pro_s!(2.0));
+}
+
+#[test]
+fn test_parse_float_quantity() {
+    assert_eq!("10 m/s".parse(), Ok(m_pro_s!(10.)));
+    assert_eq!("3.5 m^2".parse(), Ok(area!(3.5)));
+    assert_eq!("42g".parse(), Ok(gramm!(42.)));
+    assert_eq!("-0.5  s".parse(), Ok(second!(-0.5)));
+    assert_eq!("10 km/h".parse(), Ok(unit!(10., unit_pro!(UnitKind::Prefixed(SiPrefix::Kilo, NativeUnit::Meter), native!(Hour)))));
+    assert_eq!("7".parse(), Ok(unit!(7., UnitKind::None)));
+    assert_eq!("2.5 l".parse::<Unit<f32>>(), Ok(liter!(2.5)));
+}
+
+#[test]
+fn test_parse_float_quantity_errors() {
+    assert_eq!(
+        "m/s".parse::<Unit<f64>>().unwrap_err().to_string(),
+        "invalid number \"\" in quantity \"m/s\""
+    );
+    assert_eq!(
+        "1.2.3 m".parse::<Unit<f64>>().unwrap_err().to_string(),
+        "invalid number \"1.2.3\" in quantity \"1.2.3 m\""
+    );
+    assert_eq!(
+        "10 m/".parse::<Unit<f64>>().unwrap_err().to_string(),
+        "expected a unit after '/' at position 3 in unit \" m/\""
+    );
+    assert!("10 m*^2".parse::<Unit<f64>>().is_err());
+}
+
+#[test]
+fn test_duration_round_trip() {
+    let duration = hour!(1.0).to_duration();
+    assert_eq!(duration, Ok(Duration::from_secs(3600)));
+    assert_eq!(Unit::from_duration(duration.unwrap(), NativeUnit::Hour), Ok(hour!(1.0)));
+    assert_eq!(
+        Unit::new(250., UnitKind::Prefixed(SiPrefix::Milli, NativeUnit::Second)).to_duration(),
+        Ok(Duration::from_millis(250))
+    );
+}
+
+#[test]
+fn test_duration_errors() {
+    assert!(matches!(meter!(1.0).to_duration(), Err(UnitError::Incompatible { .. })));
+    assert_eq!(second!(-1.0).to_duration(), Err(UnitError::InvalidDuration { seconds: -1.0 }));
+    assert!(Unit::from_duration(Duration::from_secs(1), NativeUnit::Meter).is_err());
+}
+
+#[test]
+fn test_alternate_display() {
+    assert_eq!(format!("{}", area!(5)), "5m^2");
+    assert_eq!(format!("{:#}", area!(5)), "5m²");
+    assert_eq!(format!("{:#}", unit!(2, unit_per!(native!(Meter), unit_pow!(native!(Second), 10)))), "2m·s¹⁰");
+    assert_eq!(format!("{:#.1}", m_pro_s!(2.25)), "2.2m/s");
+    assert_eq!(format!("{:#}", second!(2.0_f64).recip()), "0.5s⁻¹");
+    assert_eq!(format!("{:#}", unit_pro!(UnitKind::None, unit_pow!(native!(Second), 2))), "s⁻²");
+    assert_eq!(format!("{}", unit_pro!(UnitKind::None, unit_pow!(native!(Second), 2))), "/s^2");
+}
+
+#[test]
+fn test_try_ops_float() {
+    assert_eq!(meter!(1.5_f64).try_add(meter!(2.)), Ok(meter!(3.5)));
+    assert_eq!(meter!(1.5_f64).try_sub(meter!(2.)), Ok(meter!(-0.5)));
+    assert_eq!(meter!(2.0_f64).try_mul(meter!(3.)), Ok(area!(6.)));
+    assert_eq!(meter!(6.0_f64).try_div(second!(3.)), Ok(m_pro_s!(2.)));
+    assert!(matches!(meter!(1.0_f64).try_add(second!(1.)), Err(UnitError::Mismatch { .. })));
+    assert_eq!(meter!(1.0_f64).try_div(second!(0.)), Err(UnitError::NotFinite));
+    assert_eq!(meter!(f64::MAX).try_add(meter!(f64::MAX)), Err(UnitError::NotFinite));
+    assert_eq!(meter!(f32::MAX).try_mul(meter!(2.)), Err(UnitError::NotFinite));
+}
+
+#[test]
+fn test_try_ops_int() {
+    assert_eq!(meter!(6_i64).try_div(second!(3)), Ok(m_pro_s!(2)));
+    assert_eq!(meter!(6_i64).try_div(second!(0)), Err(UnitError::DivByZero));
+    assert_eq!(meter!(i64::MAX).try_add(meter!(1)), Err(UnitError::Overflow));
+    assert_eq!(meter!(i32::MIN).try_sub(meter!(1)), Err(UnitError::Overflow));
+    assert_eq!(meter!(i32::MAX).try_mul(meter!(2)), Err(UnitError::Overflow));
+    assert_eq!(meter!(i32::MIN).try_div(unit!(-1, UnitKind::None)), Err(UnitError::Overflow));
+    assert!(matches!(meter!(1_i32).try_sub(second!(1)), Err(UnitError::Mismatch { .. })));
+}
+
+#[test]
+fn test_unit_registry() {
+    let mut registry = UnitRegistry::new();
+    registry.register("mile", 1609.344, native!(Meter));
+    assert_eq!(Unit::new(2.0, UnitKind::Custom("mile".into())).expand_with(&registry), Ok(meter!(3218.688)));
+    let per_hour = unit_pro!(UnitKind::Custom("mile".into()), native!(Hour));
+    assert_eq!(registry.expand(&per_hour), Ok(unit_pro!(native!(Meter), native!(Hour))));
+    assert_eq!(registry.expand(&UnitKind::Custom("ft".into())), Ok(UnitKind::Custom("ft".into())));
+}
+
+#[test]
+fn test_unit_registry_nested() {
+    let mut registry = UnitRegistry::new();
+    registry.register("yd", 0.9144, native!(Meter));
+    registry.register("acre", 4840., unit_pow!(UnitKind::Custom("yd".into()), 2));
+    let acre = Unit::new(1.0, UnitKind::Custom("acre".into())).expand_with(&registry).unwrap();
+    assert_eq!(acre.unit_ref(), &unit_pow!(native!(Meter), 2));
+    assert!((acre.value() - 4046.8564224).abs() < 1e-9);
+}
+
+#[test]
+fn test_unit_registry_cycle() {
+    let mut registry = UnitRegistry::new();
+    registry.register("a", 2., UnitKind::Custom("b".into()));
+    registry.register("b", 3., unit_per!(native!(Meter), UnitKind::Custom("a".into())));
+    assert_eq!(registry.expand(&UnitKind::Custom("a".into())), Err(UnitError::CyclicUnit("a".into())));
+}
+
+#[test]
+fn test_widening_conversions() {
+    assert_eq!(Unit::<i64>::from(meter!(3_i32)), meter!(3_i64));
+    assert_eq!(Unit::<f64>::from(m_pro_s!(-3_i32)), m_pro_s!(-3.0));
+    assert_eq!(Unit::<f64>::from(second!(1.5_f32)), second!(1.5));
+}
+
+#[test]
+fn test_narrowing_conversion() {
+    assert_eq!(Unit::<i32>::try_from(area!(42_i64)), Ok(area!(42_i32)));
+    assert_eq!(Unit::<i32>::try_from(meter!(i64::MAX)), Err(UnitError::OutOfRange));
+    assert_eq!(Unit::<i32>::try_from(meter!(i32::MIN as i64 - 1)), Err(UnitError::OutOfRange));
+}
+
+#[test]
+fn test_si_base_units() {
+    assert_eq!(Unit::new(3.0, native!(Kelvin)).to_string(), "3K");
+    assert_eq!(Unit::new(3.0, native!(Kelvin)) * Unit::new(2.0, native!(Kelvin)), unit!(6.0, unit_pow!(native!(Kelvin), 2)));
+    assert_eq!("2 mA".parse(), Ok(unit!(2., UnitKind::Prefixed(SiPrefix::Milli, NativeUnit::Ampere))));
+    assert_eq!("1 mol/l".parse(), Ok(unit!(1., unit_pro!(native!(Mole), native!(Liter)))));
+    assert_eq!("5cd".parse(), Ok(unit!(5., native!(Candela))));
+}
+
+#[test]
+fn test_si_base_dimensions() {
+    assert_eq!(native!(Ampere).dimension(), Some(BTreeMap::from([(NativeUnit::Ampere, 1)])));
+    assert_eq!(unit!(1., unit_per!(native!(Ampere), native!(Second))).dimension_string(), "T I");
+    assert_eq!(Unit::new(1., native!(Kelvin)).expect_dimension(BaseDimension::Temperature), Ok(Unit::new(1., native!(Kelvin))));
+    assert_eq!(Unit::new(2., native!(Mole)).to_unit_system(UnitSystem::Imperial), Some(Unit::new(2., native!(Mole))));
+}
+
+#[test]
+fn test_convert_temperature() {
+    let celsius = |value| Unit::new(value, TempScale::Celsius.unit());
+    let fahrenheit = |value| Unit::new(value, TempScale::Fahrenheit.unit());
+    assert_eq!(celsius(0.).convert_temperature(TempScale::Kelvin), Ok(Unit::new(273.15, native!(Kelvin))));
+    assert_eq!(celsius(100.).convert_temperature(TempScale::Kelvin), Ok(Unit::new(373.15, native!(Kelvin))));
+    assert_eq!(fahrenheit(32.).convert_temperature(TempScale::Celsius), Ok(celsius(0.)));
+    assert_eq!(fahrenheit(-40.).convert_temperature(TempScale::Celsius), Ok(celsius(-40.)));
+    assert_eq!(celsius(20.).to_string(), "20°C");
+}
+
+#[test]
+fn test_convert_temperature_round_trip() {
+    for value in [-459.67, -40., 0., 32., 98.6, 212., 451.] {
+        let back = Unit::new(value, TempScale::Fahrenheit.unit())
+            .convert_temperature(TempScale::Celsius)
+            .and_then(|celsius| celsius.convert_temperature(TempScale::Fahrenheit))
+            .unwrap();
+        assert!((back.value() - value).abs() < 1e-12);
+    }
+    assert!(meter!(1.).convert_temperature(TempScale::Kelvin).is_err());
+}
+
+#[test]
+fn test_simplify() {
+    assert_eq!(unit_pro!(unit_per!(native!(Meter), native!(Second)), native!(Second)).simplify(), native!(Meter));
+    assert_eq!(unit_pro!(m_pro_s!(1.).unit(), m_pro_s!(1.).unit()).simplify(), UnitKind::None);
+    assert_eq!(
+        unit_per!(unit_pro!(native!(Second), native!(Meter)), unit_per!(native!(Gramm), native!(Second))).simplify(),
+        unit_pro!(unit_per!(native!(Gramm), unit_pow!(native!(Second), 2)), native!(Meter))
+    );
+    assert_eq!(
+        unit_pro!(UnitKind::Custom("ft".into()), unit_pow!(native!(Hour), 2)).simplify(),
+        unit_pro!(UnitKind::Custom("ft".into()), unit_pow!(native!(Hour), 2))
+    );
+    assert_eq!(unit_pro!(native!(Meter), native!(Hour)).simplify(), unit_pro!(native!(Meter), native!(Hour)));
+}
+
+#[test]
+fn test_unit_simplify() {
+    let distance = m_pro_s!(3.) * second!(2.) / meter!(1.) * meter!(4.);
+    assert_eq!(distance.simplify(), meter!(24.));
+    assert_eq!((area!(2.) / meter!(1.) / meter!(2.)).simplify(), unit!(1., UnitKind::None));
+}
+
+#[test]
+fn test_approx_eq() {
+    let speed = m_pro_s!(10_f64);
+    let drifted = m_pro_s!(10. + 1e-12);
+    assert!(speed.approx_eq(&drifted, 1e-9));
+    assert!(!speed.approx_eq(&drifted, 0.));
+    assert!(minute!(1_f64).approx_eq(&second!(60.), 1e-9));
+    assert!(meter!(2_f64).approx_eq(&(m_pro_s!(1.) * second!(2.)), 0.));
+    assert!(!meter!(1_f64).approx_eq(&second!(1.), f64::INFINITY));
+    assert!(!m_pro_s!(1_f64).approx_eq(&area!(1.), f64::INFINITY));
+}
+
+#[test]
+fn test_approx_eq_f32() {
+    assert!(meter!(1.5_f32).approx_eq(&meter!(1.5_f32 + 1e-6), 1e-5));
+    assert!(!meter!(1.5_f32).approx_eq(&meter!(1.5_f32 + 1e-6), 0.));
+    assert!(!meter!(1_f32).approx_eq(&gramm!(1_f32), f32::INFINITY));
+}
@@ -0,0 +1,23 @@
+use unios::*;
+
+#[test]
+fn test_native_unit_macros() {
+    assert_eq!(meter!(1.).to_string(), "1m");
+    assert_eq!(liter!(1.).to_string(), "1l");
+    assert_eq!(gramm!(1.).to_string(), "1g");
+    assert_eq!(second!(1.).to_string(), "1s");
+    assert_eq!(minute!(1.).to_string(), "1min");
+    assert_eq!(hour!(1.).to_string(), "1h");
+    assert_eq!(day!(1.).to_string(), "1d");
+    assert_eq!(week!(1.).to_string(), "1w");
+    assert_eq!(year!(1.).to_string(), "1y");
+}
+
+#[test]
+fn test_compound_unit_macros() {
+    assert_eq!(unit!(1., UnitKind::None).to_string(), "1");
+    assert_eq!(m_pro_s!(1.).to_string(), "1m/s");
+    assert_eq!(area!(1.).to_string(), "1m^2");
+    assert_eq!(volume!(1.).to_string(), "1m^3");
+    assert_eq!(quantities![1., 2. => Meter], vec![meter!(1.), meter!(2.)]);
+}